@@ -0,0 +1,27 @@
+use lew::RichEditor;
+use yew::{html, Component, Context, Html};
+
+const EDITOR_ID: &str = "editor";
+
+struct Root;
+
+impl Component for Root {
+    type Message = ();
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+    fn view(&self, _ctx: &Context<Self>) -> Html {
+        html! {
+            <div>
+                <RichEditor id = { EDITOR_ID } text = "Click here and start typing" />
+            </div>
+        }
+    }
+}
+
+fn main() {
+    yew::Renderer::<Root>::new().render();
+}
@@ -1,9 +1,17 @@
+#[cfg(feature = "preview")]
+pub use self::preview::*;
 pub use self::rich::*;
 pub use self::simple::*;
 
+#[cfg(feature = "preview")]
+mod preview;
 mod rich;
 mod simple;
 
 pub trait Widget {
     fn build(&self) -> yew::Html;
+
+    fn key_binding(&self) -> Option<(keymap::Shortcut, keymap::Action)> {
+        None
+    }
 }
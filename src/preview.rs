@@ -0,0 +1,266 @@
+use std::time::Duration;
+
+use pulldown_cmark::{html as cmark_html, CowStr, Event, Options, Parser};
+use web_sys::HtmlInputElement;
+use yew::platform::spawn_local;
+use yew::platform::time::sleep;
+use yew::{html, AttrValue, Callback, Component, Context, Html, MouseEvent, Properties, TargetCast};
+
+pub use self::sanitize::Sanitizer;
+
+pub mod sanitize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkdownExtensions {
+    pub tables: bool,
+    pub strikethrough: bool,
+    pub tasklists: bool,
+    pub footnotes: bool,
+    pub smart_punctuation: bool,
+    pub heading_attributes: bool,
+}
+
+impl Default for MarkdownExtensions {
+    fn default() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            tasklists: true,
+            footnotes: false,
+            smart_punctuation: false,
+            heading_attributes: false,
+        }
+    }
+}
+
+impl MarkdownExtensions {
+    pub fn none() -> Self {
+        Self {
+            tables: false,
+            strikethrough: false,
+            tasklists: false,
+            footnotes: false,
+            smart_punctuation: false,
+            heading_attributes: false,
+        }
+    }
+
+    pub fn all() -> Self {
+        Self {
+            tables: true,
+            strikethrough: true,
+            tasklists: true,
+            footnotes: true,
+            smart_punctuation: true,
+            heading_attributes: true,
+        }
+    }
+
+    pub fn options(&self) -> Options {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_STRIKETHROUGH, self.strikethrough);
+        options.set(Options::ENABLE_TASKLISTS, self.tasklists);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        options.set(Options::ENABLE_HEADING_ATTRIBUTES, self.heading_attributes);
+        options
+    }
+}
+
+pub fn render_markdown(text: &str, extensions: MarkdownExtensions) -> String {
+    let mut html_output = String::new();
+    cmark_html::push_html(&mut html_output, Parser::new_ext(text, extensions.options()));
+    html_output
+}
+
+pub fn render_markdown_with_tasks(text: &str, extensions: MarkdownExtensions) -> String {
+    let events = Parser::new_ext(text, extensions.options())
+        .into_offset_iter()
+        .map(|(event, range)| match event {
+            Event::Html(html) => Event::Html(escape_task_attributes(&html).into()),
+            Event::TaskListMarker(checked) => {
+                let pos = text[..range.start].chars().count();
+                let checked = if checked { " checked=\"\"" } else { "" };
+                Event::Html(CowStr::from(format!(
+                    "<input type=\"checkbox\" data-task=\"{pos}\"{checked}/>\n"
+                )))
+            },
+            event => event,
+        });
+
+    let mut html_output = String::new();
+    cmark_html::push_html(&mut html_output, events);
+    html_output
+}
+
+fn escape_task_attributes(html: &str) -> String {
+    const ATTRIBUTE: &str = "data-task";
+
+    let mut output = String::with_capacity(html.len());
+    let mut last = 0;
+    for (pos, _) in html.to_ascii_lowercase().match_indices(ATTRIBUTE) {
+        output.push_str(&html[last..pos]);
+        output.push_str("data-x-task");
+        last = pos + ATTRIBUTE.len();
+    }
+    output.push_str(&html[last..]);
+    output
+}
+
+pub fn render_markdown_sanitized(text: &str, extensions: MarkdownExtensions, sanitizer: &Sanitizer) -> String {
+    sanitizer.sanitize(&render_markdown(text, extensions))
+}
+
+pub enum MarkdownPreviewMsg {
+    Render(u64),
+}
+
+pub struct MarkdownPreview {
+    id: String,
+    class: String,
+    html: AttrValue,
+    generation: u64,
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct MarkdownPreviewProps {
+    #[prop_or_default]
+    pub id: String,
+
+    #[prop_or("lew-preview".to_string())]
+    pub class: String,
+
+    #[prop_or_default]
+    pub text: String,
+
+    #[prop_or_default]
+    pub extensions: MarkdownExtensions,
+
+    #[prop_or(Some(Sanitizer::default()))]
+    pub sanitizer: Option<Sanitizer>,
+
+    #[prop_or(150)]
+    pub debounce_ms: u64,
+
+    #[prop_or_default]
+    pub ontoggletask: Option<Callback<usize>>,
+}
+
+impl MarkdownPreview {
+    fn render(props: &MarkdownPreviewProps) -> AttrValue {
+        if props.ontoggletask.is_none() {
+            let html = render_markdown(&props.text, props.extensions);
+            return match &props.sanitizer {
+                Some(sanitizer) => sanitizer.sanitize(&html).into(),
+                None => html.into(),
+            };
+        }
+
+        let html = render_markdown_with_tasks(&props.text, props.extensions);
+        match &props.sanitizer {
+            Some(sanitizer) => sanitizer
+                .clone()
+                .with_attribute("input", "data-task")
+                .sanitize(&html)
+                .into(),
+            None => html.into(),
+        }
+    }
+}
+
+impl Component for MarkdownPreview {
+    type Message = MarkdownPreviewMsg;
+    type Properties = MarkdownPreviewProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            id: ctx.props().id.clone(),
+            class: ctx.props().class.clone(),
+            html: Self::render(ctx.props()),
+            generation: 0,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MarkdownPreviewMsg::Render(generation) if generation == self.generation => {
+                self.html = Self::render(ctx.props());
+                true
+            },
+            MarkdownPreviewMsg::Render(_) => false,
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        let props = ctx.props();
+        self.id = props.id.clone();
+        self.class = props.class.clone();
+        self.generation += 1;
+        if props.debounce_ms == 0
+            || props.text == old_props.text
+            || props.sanitizer != old_props.sanitizer
+            || props.ontoggletask.is_some() != old_props.ontoggletask.is_some()
+        {
+            self.html = Self::render(props);
+            return true;
+        }
+
+        let generation = self.generation;
+        let delay = Duration::from_millis(props.debounce_ms);
+        let link = ctx.link().clone();
+        spawn_local(async move {
+            sleep(delay).await;
+            link.send_message(MarkdownPreviewMsg::Render(generation));
+        });
+        props.id != old_props.id || props.class != old_props.class
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onclick = ctx.props().ontoggletask.clone().map(|ontoggletask| {
+            Callback::from(move |event: MouseEvent| {
+                let task = event
+                    .target_dyn_into::<HtmlInputElement>()
+                    .and_then(|input| input.get_attribute("data-task"))
+                    .and_then(|pos| pos.parse().ok());
+                if let Some(pos) = task {
+                    ontoggletask.emit(pos);
+                }
+            })
+        });
+
+        html! {
+            <div id = { self.id.clone() } class = { self.class.clone() } { onclick }>
+                { Html::from_html_unchecked(self.html.clone()) }
+            </div>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_markers_carry_the_box_offset() {
+        let html = render_markdown_with_tasks("- a\n- [x] é\n  - [ ] b", MarkdownExtensions::default());
+        assert!(html.contains("<input type=\"checkbox\" data-task=\"6\" checked=\"\"/>"));
+        assert!(html.contains("<input type=\"checkbox\" data-task=\"16\"/>"));
+    }
+
+    #[test]
+    fn author_task_attributes_are_neutralized() {
+        let text = "<input type=\"checkbox\" DATA-TASK=\"0\">\n\nx <input type=checkbox data-task=1>";
+        let html = render_markdown_with_tasks(text, MarkdownExtensions::default());
+        assert!(!html.to_ascii_lowercase().contains("data-task="));
+
+        let sanitizer = Sanitizer::default().with_attribute("input", "data-task");
+        assert!(!sanitizer.sanitize(&html).contains("data-task"));
+    }
+
+    #[test]
+    fn default_sanitizer_strips_task_attributes() {
+        let html = "<input type=\"checkbox\" data-task=\"0\">";
+        assert_eq!(Sanitizer::default().sanitize(html), "<input type=\"checkbox\">");
+    }
+}
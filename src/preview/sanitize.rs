@@ -0,0 +1,335 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+pub use crate::markup::decode_entities;
+use crate::markup::{normalize_url, tokenize, Token};
+
+const DEFAULT_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "code",
+    "dd",
+    "del",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "input",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "samp",
+    "section",
+    "small",
+    "span",
+    "strike",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "tt",
+    "ul",
+];
+
+const DEFAULT_ATTRIBUTES: &[(&str, &[&str])] = &[
+    ("*", &["title", "lang", "dir"]),
+    ("a", &["href", "id"]),
+    ("code", &["class"]),
+    ("div", &["class", "id"]),
+    ("img", &["src", "alt", "width", "height"]),
+    ("input", &["type", "checked", "disabled"]),
+    ("li", &["id"]),
+    ("ol", &["start"]),
+    ("sup", &["class", "id"]),
+    ("td", &["style", "align"]),
+    ("th", &["style", "align"]),
+];
+
+const DROPPED_CONTENT_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "template", "noscript", "textarea", "title", "xmp", "noembed",
+    "noframes", "frameset", "frame", "svg", "math",
+];
+
+const URL_ATTRIBUTES: &[&str] = &["href", "src", "cite", "action", "formaction", "poster", "background"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sanitizer {
+    pub tags: BTreeSet<String>,
+    pub attributes: BTreeMap<String, BTreeSet<String>>,
+    pub url_schemes: BTreeSet<String>,
+    pub link_rel: Option<String>,
+}
+
+impl Default for Sanitizer {
+    fn default() -> Self {
+        Self {
+            tags: DEFAULT_TAGS.iter().map(|tag| tag.to_string()).collect(),
+            attributes: DEFAULT_ATTRIBUTES
+                .iter()
+                .map(|(tag, attributes)| {
+                    (
+                        tag.to_string(),
+                        attributes.iter().map(|attribute| attribute.to_string()).collect(),
+                    )
+                })
+                .collect(),
+            url_schemes: ["http", "https", "mailto"]
+                .iter()
+                .map(|scheme| scheme.to_string())
+                .collect(),
+            link_rel: Some("nofollow noopener noreferrer".to_string()),
+        }
+    }
+}
+
+impl Sanitizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.insert(tag.into().to_lowercase());
+        self
+    }
+
+    pub fn without_tag(mut self, tag: &str) -> Self {
+        self.tags.remove(&tag.to_lowercase());
+        self
+    }
+
+    pub fn with_attribute(mut self, tag: impl Into<String>, attribute: impl Into<String>) -> Self {
+        self.attributes
+            .entry(tag.into().to_lowercase())
+            .or_default()
+            .insert(attribute.into().to_lowercase());
+        self
+    }
+
+    pub fn with_url_scheme(mut self, scheme: impl Into<String>) -> Self {
+        self.url_schemes.insert(scheme.into().to_lowercase());
+        self
+    }
+
+    pub fn with_link_rel(mut self, rel: Option<String>) -> Self {
+        self.link_rel = rel;
+        self
+    }
+
+    pub fn is_allowed_url(&self, url: &str) -> bool {
+        let url = normalize_url(url);
+        match url.find([':', '/', '?', '#']) {
+            Some(pos) if url[pos..].starts_with(':') => self.url_schemes.contains(&url[..pos].to_lowercase()),
+            _ => true,
+        }
+    }
+
+    pub fn sanitize(&self, html: &str) -> String {
+        let mut output = String::with_capacity(html.len());
+        let mut dropped: Option<String> = None;
+
+        for token in tokenize(html) {
+            if let Some(dropped_tag) = &dropped {
+                if matches!(&token, Token::End(name) if name == dropped_tag) {
+                    dropped = None;
+                }
+                continue;
+            }
+
+            match token {
+                Token::Text(text) => push_text(&mut output, &text),
+                Token::Start(name, _) if DROPPED_CONTENT_TAGS.contains(&name.as_str()) => dropped = Some(name),
+                Token::Start(name, attributes) if self.tags.contains(&name) => {
+                    self.push_start_tag(&mut output, &name, attributes)
+                },
+                Token::End(name) if self.tags.contains(&name) => {
+                    output.push_str("</");
+                    output.push_str(&name);
+                    output.push('>');
+                },
+                _ => (),
+            }
+        }
+        output
+    }
+
+    fn is_allowed_attribute(&self, tag: &str, attribute: &str) -> bool {
+        [tag, "*"].iter().any(|tag| {
+            self.attributes
+                .get(*tag)
+                .is_some_and(|attributes| attributes.contains(attribute))
+        })
+    }
+
+    fn push_start_tag(&self, output: &mut String, name: &str, attributes: Vec<(String, String)>) {
+        let mut attributes: Vec<_> = attributes
+            .into_iter()
+            .filter(|(attribute, _)| !attribute.starts_with("on") && self.is_allowed_attribute(name, attribute))
+            .filter(|(attribute, value)| !URL_ATTRIBUTES.contains(&attribute.as_str()) || self.is_allowed_url(value))
+            .filter(|(attribute, value)| attribute != "style" || is_text_align_style(value))
+            .map(|(attribute, value)| (attribute, decode_entities(&value)))
+            .collect();
+
+        if name == "input"
+            && !attributes
+                .iter()
+                .any(|(attribute, value)| attribute == "type" && value.eq_ignore_ascii_case("checkbox"))
+        {
+            return;
+        }
+        if name == "a" {
+            if let Some(rel) = &self.link_rel {
+                attributes.push(("rel".to_string(), rel.clone()));
+            }
+        }
+
+        output.push('<');
+        output.push_str(name);
+        for (attribute, value) in attributes {
+            output.push(' ');
+            output.push_str(&attribute);
+            output.push_str("=\"");
+            push_escaped(output, &value);
+            output.push('"');
+        }
+        output.push('>');
+    }
+}
+
+fn is_text_align_style(value: &str) -> bool {
+    let value = value.trim().trim_end_matches(';').replace(' ', "").to_ascii_lowercase();
+    matches!(
+        value.as_str(),
+        "text-align:left" | "text-align:center" | "text-align:right"
+    )
+}
+
+fn push_text(output: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+fn push_escaped(output: &mut String, value: &str) {
+    for ch in value.chars() {
+        match ch {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            ch => output.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sanitize(html: &str) -> String {
+        Sanitizer::default().sanitize(html)
+    }
+
+    #[test]
+    fn drops_scripts_and_their_content() {
+        assert_eq!(sanitize("a<script>alert(1)</script>b"), "ab");
+        assert_eq!(sanitize("<SCRIPT SRC=//evil.js></SCRIPT>"), "");
+        assert_eq!(sanitize("<svg><script>alert(1)</script></svg>ok"), "ok");
+        assert_eq!(sanitize("<style>*{}</style><iframe src=x></iframe>"), "");
+    }
+
+    #[test]
+    fn drops_event_handlers() {
+        assert_eq!(sanitize("<img src=x onerror=alert(1)>"), "<img src=\"x\">");
+        assert_eq!(sanitize("<p onclick=\"alert(1)\">a</p>"), "<p>a</p>");
+        assert_eq!(
+            sanitize("<a href=\"/\" ONMOUSEOVER=alert(1)>a</a>"),
+            sanitize("<a href=\"/\">a</a>")
+        );
+    }
+
+    #[test]
+    fn filters_url_schemes() {
+        assert_eq!(
+            sanitize("<a href=\"javascript:alert(1)\">a</a>"),
+            "<a rel=\"nofollow noopener noreferrer\">a</a>"
+        );
+        assert_eq!(
+            sanitize("<a href=\"jav&#x09;ascript:alert(1)\">a</a>"),
+            "<a rel=\"nofollow noopener noreferrer\">a</a>"
+        );
+        assert_eq!(
+            sanitize("<a href=\" JaVaScRiPt:alert(1)\">a</a>"),
+            "<a rel=\"nofollow noopener noreferrer\">a</a>"
+        );
+        assert_eq!(sanitize("<img src=\"data:text/html;base64,PHNjcmlwdD4=\">"), "<img>");
+        assert_eq!(
+            sanitize("<a href=\"https://example.com/?a=1&amp;b=2\">a</a>"),
+            "<a href=\"https://example.com/?a=1&amp;b=2\" rel=\"nofollow noopener noreferrer\">a</a>"
+        );
+        assert_eq!(sanitize("<img src=\"/images/a.png\">"), "<img src=\"/images/a.png\">");
+    }
+
+    #[test]
+    fn filters_entity_encoded_markdown_links() {
+        use crate::{render_markdown, MarkdownExtensions};
+
+        let html = render_markdown("[x](javascript&#58;alert(1))", MarkdownExtensions::default());
+        assert!(!sanitize(&html).contains("href"));
+    }
+
+    #[test]
+    fn escapes_text_and_attribute_values() {
+        assert_eq!(sanitize("<p>1 &lt; 2</p>"), "<p>1 &lt; 2</p>");
+        assert_eq!(
+            sanitize("<img alt='\"><script>alert(1)</script>'>"),
+            "<img alt=\"&quot;&gt;&lt;script&gt;alert(1)&lt;/script&gt;\">"
+        );
+        assert_eq!(sanitize("<unknown>text</unknown>"), "text");
+    }
+
+    #[test]
+    fn keeps_only_checkbox_inputs_and_safe_styles() {
+        assert_eq!(sanitize("<input type=\"text\" value=\"x\">"), "");
+        assert_eq!(
+            sanitize("<input type=\"checkbox\" checked=\"\" disabled=\"\">"),
+            "<input type=\"checkbox\" checked=\"\" disabled=\"\">"
+        );
+        assert_eq!(
+            sanitize("<td style=\"text-align: center\">a</td>"),
+            "<td style=\"text-align: center\">a</td>"
+        );
+        assert_eq!(
+            sanitize("<td style=\"background:url(javascript:x)\">a</td>"),
+            "<td>a</td>"
+        );
+    }
+}
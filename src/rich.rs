@@ -1,51 +1,118 @@
-#![allow(dead_code)]
+use unicode_segmentation::UnicodeSegmentation;
+use yew::{html, Callback, Component, Context, FocusEvent, Html, KeyboardEvent, Properties};
 
-use yew::{html, Component, Context, Html, Properties};
+use crate::toolbar::unit::{self, SelectionUnit};
+
+pub enum RichEditorMsg {
+    KeyDown(KeyboardEvent),
+    Focus,
+    Blur,
+}
 
 pub struct RichEditor {
     id: String,
     class: String,
     caret_index: usize,
     text: String,
+    oninput: Callback<String>,
+    onchange: Callback<String>,
+    focused: bool,
+    modified: bool,
 }
 
-#[derive(Clone, Properties, Default, PartialEq)]
+#[derive(Clone, Properties, PartialEq)]
 pub struct RichEditorProps {
+    #[prop_or_default]
     pub id: String,
+
+    #[prop_or("lew-rich".to_string())]
     pub class: String,
+
+    #[prop_or_default]
     pub caret_index: usize,
+
+    #[prop_or_default]
     pub text: String,
+
+    #[prop_or(Callback::noop())]
+    pub oninput: Callback<String>,
+
+    #[prop_or(Callback::noop())]
+    pub onchange: Callback<String>,
 }
 
 impl Component for RichEditor {
-    type Message = ();
+    type Message = RichEditorMsg;
     type Properties = RichEditorProps;
 
     fn create(ctx: &Context<Self>) -> Self {
-        Self {
+        let mut editor = Self {
             id: ctx.props().id.clone(),
             class: ctx.props().class.clone(),
             caret_index: ctx.props().caret_index,
             text: ctx.props().text.clone(),
+            oninput: ctx.props().oninput.clone(),
+            onchange: ctx.props().onchange.clone(),
+            focused: false,
+            modified: false,
+        };
+        editor.clamp_caret();
+        editor
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RichEditorMsg::KeyDown(event) => match self.handle_key(&event) {
+                Some(edited) => {
+                    event.prevent_default();
+                    if edited {
+                        self.modified = true;
+                        self.oninput.emit(self.text.clone());
+                    }
+                    true
+                },
+                None => false,
+            },
+            RichEditorMsg::Focus => {
+                self.focused = true;
+                true
+            },
+            RichEditorMsg::Blur => {
+                self.focused = false;
+                if self.modified {
+                    self.modified = false;
+                    self.onchange.emit(self.text.clone());
+                }
+                true
+            },
         }
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
         self.id = ctx.props().id.clone();
         self.class = ctx.props().class.clone();
-        self.caret_index = ctx.props().caret_index;
-        self.text = ctx.props().text.clone();
+        if ctx.props().text != self.text {
+            self.text = ctx.props().text.clone();
+            self.caret_index = ctx.props().caret_index;
+        } else if ctx.props().caret_index != old_props.caret_index {
+            self.caret_index = ctx.props().caret_index;
+        }
+        self.oninput = ctx.props().oninput.clone();
+        self.onchange = ctx.props().onchange.clone();
+        self.clamp_caret();
         true
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
-        self.update_content();
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let onkeydown = ctx.link().callback(RichEditorMsg::KeyDown);
+        let onfocus = ctx.link().callback(|_: FocusEvent| RichEditorMsg::Focus);
+        let onblur = ctx.link().callback(|_: FocusEvent| RichEditorMsg::Blur);
+
         html! {
-            <div id = { self.id.clone() } class = { self.class.clone() }>
-                <div class = "lew-rich">
+            <div id = { self.id.clone() } class = { self.class.clone() } tabindex = "0" { onkeydown } { onfocus } { onblur }>
+                <div class = "lew-rich__content">
                     { self.generate_content() }
                 </div>
-                <span class = "lew-rich-blinking-cursor">{ "|" }</span>
             </div>
         }
     }
@@ -53,39 +120,79 @@ impl Component for RichEditor {
 
 impl RichEditor {
     fn text_before_caret(&self) -> &str {
-        if self.caret_index == 0 {
-            ""
-        } else {
-            &self.text[..self.caret_index]
-        }
+        &self.text[..self.caret_index]
     }
 
     fn text_after_caret(&self) -> &str {
-        if self.caret_index == self.text.len() {
-            ""
-        } else {
-            &self.text[self.caret_index..]
-        }
+        &self.text[self.caret_index..]
     }
 
     fn generate_content(&self) -> Html {
+        let caret = if self.focused {
+            html! {
+                <span class = "lew-rich-cursor-placeholder">
+                    <span class = "lew-rich-blinking-cursor">{ "|" }</span>
+                </span>
+            }
+        } else {
+            html! {}
+        };
+
         html! {
-            <>{ self.text_before_caret() }<span class = "lew-rich-cursor-placeholder">{ "|" }</span>{ self.text_after_caret() }</>
+            <>{ self.text_before_caret() }{ caret }{ self.text_after_caret() }</>
+        }
+    }
+
+    fn clamp_caret(&mut self) {
+        self.caret_index = self.caret_index.min(self.text.len());
+        while !self.text.is_char_boundary(self.caret_index) {
+            self.caret_index -= 1;
         }
     }
 
-    fn update_content(&self) {
-        // let _document = document();
-        // let editor = if self.id.is_empty() {
-        //     document.get_elements_by_class_name("lew-editor").item(0)
-        // } else {
-        //     document
-        //         .get_element_by_id(&self.id)
-        //         .and_then(|el| el.first_element_child())
-        // }
-        // .expect("Cannot find editor element");
+    fn handle_key(&mut self, event: &KeyboardEvent) -> Option<bool> {
+        if event.ctrl_key() || event.meta_key() || event.alt_key() {
+            return None;
+        }
 
-        todo!()
+        let key = event.key();
+        match key.as_str() {
+            "Backspace" => Some(self.delete_char_before_caret()),
+            "Delete" => Some(self.delete_char_after_caret()),
+            "Enter" => {
+                self.type_item("\n");
+                Some(true)
+            },
+            "ArrowLeft" => {
+                self.move_before();
+                Some(false)
+            },
+            "ArrowRight" => {
+                self.move_after();
+                Some(false)
+            },
+            "ArrowUp" => {
+                self.move_up();
+                Some(false)
+            },
+            "ArrowDown" => {
+                self.move_down();
+                Some(false)
+            },
+            "Home" => {
+                self.caret_index = self.line_start(self.caret_index);
+                Some(false)
+            },
+            "End" => {
+                self.caret_index = self.line_end(self.caret_index);
+                Some(false)
+            },
+            _ if key.chars().count() == 1 => {
+                self.type_item(&key);
+                Some(true)
+            },
+            _ => None,
+        }
     }
 
     fn type_item(&mut self, item: &str) {
@@ -93,26 +200,39 @@ impl RichEditor {
         self.caret_index += item.len();
     }
 
-    fn before_char_len(&self) -> usize {
-        self.text_before_caret()
-            .chars()
-            .next_back()
-            .map(|ch| ch.len_utf8())
-            .unwrap_or(0)
+    fn caret_chars(&self) -> usize {
+        SelectionUnit::Byte.convert(&self.text, self.caret_index, SelectionUnit::Char)
     }
 
-    fn after_char_len(&self) -> usize {
-        self.text_after_caret()
-            .chars()
-            .next()
-            .map(|ch| ch.len_utf8())
-            .unwrap_or(0)
+    fn char_to_byte(&self, pos: usize) -> usize {
+        SelectionUnit::Char.convert(&self.text, pos, SelectionUnit::Byte)
+    }
+
+    fn before_grapheme_len(&self) -> usize {
+        let start = unit::grapheme_floor(&self.text, self.caret_chars().saturating_sub(1));
+        self.caret_index - self.char_to_byte(start)
+    }
+
+    fn after_grapheme_len(&self) -> usize {
+        let end = unit::grapheme_ceil(&self.text, self.caret_chars() + 1);
+        self.char_to_byte(end) - self.caret_index
     }
 
     fn delete_char_before_caret(&mut self) -> bool {
         if self.caret_index > 0 {
-            self.caret_index -= self.before_char_len();
-            self.text.remove(self.caret_index);
+            let end = self.caret_index;
+            self.caret_index -= self.before_grapheme_len();
+            self.text.replace_range(self.caret_index..end, "");
+            true
+        } else {
+            false
+        }
+    }
+
+    fn delete_char_after_caret(&mut self) -> bool {
+        if self.caret_index < self.text.len() {
+            let end = self.caret_index + self.after_grapheme_len();
+            self.text.replace_range(self.caret_index..end, "");
             true
         } else {
             false
@@ -123,7 +243,7 @@ impl RichEditor {
         if self.caret_index == 0 {
             false
         } else {
-            self.caret_index -= self.before_char_len();
+            self.caret_index -= self.before_grapheme_len();
             true
         }
     }
@@ -132,8 +252,112 @@ impl RichEditor {
         if self.caret_index == self.text.len() {
             false
         } else {
-            self.caret_index += self.after_char_len();
+            self.caret_index += self.after_grapheme_len();
             true
         }
     }
+
+    fn move_up(&mut self) -> bool {
+        let start = self.line_start(self.caret_index);
+        if start == 0 {
+            return false;
+        }
+
+        let column = self.text[start..self.caret_index].graphemes(true).count();
+        self.caret_index = self.line_index_at_column(self.line_start(start - 1), column);
+        true
+    }
+
+    fn move_down(&mut self) -> bool {
+        let end = self.line_end(self.caret_index);
+        if end == self.text.len() {
+            return false;
+        }
+
+        let column = self.text[self.line_start(self.caret_index)..self.caret_index]
+            .graphemes(true)
+            .count();
+        self.caret_index = self.line_index_at_column(end + 1, column);
+        true
+    }
+
+    fn line_start(&self, index: usize) -> usize {
+        self.text[..index].rfind('\n').map(|pos| pos + 1).unwrap_or(0)
+    }
+
+    fn line_end(&self, index: usize) -> usize {
+        self.text[index..]
+            .find('\n')
+            .map(|pos| index + pos)
+            .unwrap_or(self.text.len())
+    }
+
+    fn line_index_at_column(&self, line_start: usize, column: usize) -> usize {
+        let line_end = self.line_end(line_start);
+        self.text[line_start..line_end]
+            .grapheme_indices(true)
+            .nth(column)
+            .map(|(pos, _)| line_start + pos)
+            .unwrap_or(line_end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor(text: &str, caret_index: usize) -> RichEditor {
+        RichEditor {
+            id: String::new(),
+            class: String::new(),
+            caret_index,
+            text: text.to_string(),
+            oninput: Callback::noop(),
+            onchange: Callback::noop(),
+            focused: false,
+            modified: false,
+        }
+    }
+
+    #[test]
+    fn backspace_removes_a_whole_grapheme() {
+        let text = "a👩\u{200d}👩\u{200d}👧";
+        let mut editor = editor(text, text.len());
+        assert!(editor.delete_char_before_caret());
+        assert_eq!((editor.text.as_str(), editor.caret_index), ("a", 1));
+
+        let mut editor = self::editor("e\u{301}x", 3);
+        assert!(editor.delete_char_before_caret());
+        assert_eq!((editor.text.as_str(), editor.caret_index), ("x", 0));
+    }
+
+    #[test]
+    fn delete_removes_a_whole_grapheme() {
+        let mut editor = editor("👍🏽b", 0);
+        assert!(editor.delete_char_after_caret());
+        assert_eq!(editor.text, "b");
+    }
+
+    #[test]
+    fn vertical_arrows_keep_grapheme_columns() {
+        let text = "👍🏽b\nxyz";
+        let mut editor = editor(text, 8);
+        assert!(editor.move_down());
+        assert_eq!(editor.caret_index, 11);
+        assert!(editor.move_up());
+        assert_eq!(editor.caret_index, 8);
+        assert!(!editor.move_up());
+    }
+
+    #[test]
+    fn arrows_step_over_graphemes() {
+        let text = "a👍🏽b";
+        let mut editor = editor(text, 1);
+        assert!(editor.move_after());
+        assert_eq!(editor.caret_index, 9);
+        assert!(editor.move_before());
+        assert_eq!(editor.caret_index, 1);
+        assert!(editor.move_before());
+        assert!(!editor.move_before());
+    }
 }
@@ -1,9 +1,49 @@
-use yew::{html, Callback, Component, Context, Html, InputEvent, Properties};
+use std::cell::RefCell;
+use std::rc::Rc;
 
+use wasm_bindgen::JsCast;
+use web_sys::{ClipboardEvent, DataTransfer, Event, File, HtmlTextAreaElement};
+use yew::{
+    html, Callback, Component, Context, ContextProvider, DragEvent, Html, InputEvent, KeyboardEvent, MouseEvent,
+    NodeRef, Properties, TargetCast,
+};
+
+pub use self::history::{EditKind, History};
+pub use self::keymap::{Keymap, Shortcut};
 pub use self::toolbar::SimpleToolbar;
+use crate::markup::{html_to_markdown, is_rich_html};
+#[cfg(feature = "preview")]
+use crate::preview::{MarkdownExtensions, MarkdownPreview};
+use crate::toolbar::link::{apply_link, looks_like_url, validate_url, LinkDraft, DEFAULT_LINK_SCHEMES};
+use crate::toolbar::{list, EditorContext, EditorState, IndentStyle, ReplaceFmt, Selection, UnselectedApplyMode};
+use crate::upload::{upload_files, UploadError, UploaderRef};
 use crate::Widget;
 
+pub mod history;
+pub mod keymap;
+pub mod markup;
 pub mod toolbar;
+pub mod upload;
+
+#[cfg(feature = "preview")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditorTab {
+    Write,
+    Preview,
+}
+
+pub enum SimpleEditorMsg {
+    Input(EditKind),
+    Select,
+    Undo,
+    Redo,
+    Apply(EditorState),
+    Files(Vec<File>),
+    #[cfg(feature = "preview")]
+    SelectTab(EditorTab),
+    #[cfg(feature = "preview")]
+    ToggleTask(usize),
+}
 
 pub struct SimpleEditor {
     id: String,
@@ -14,7 +54,31 @@ pub struct SimpleEditor {
     placeholder: String,
     text: String,
     toolbar: Option<Html>,
+    controlled: bool,
     oninput: Callback<InputEvent>,
+    onchange: Callback<String>,
+    onselectionchange: Callback<Selection>,
+    keymap: Option<Keymap>,
+    toolbar_keymap: Rc<RefCell<Keymap>>,
+    continue_lists: bool,
+    renumber_lists: bool,
+    indent: Option<IndentStyle>,
+    ondrop: Callback<DragEvent>,
+    ondragover: Callback<DragEvent>,
+    onpaste: Callback<Event>,
+    onfiles: Option<Callback<Vec<File>>>,
+    uploader: Option<UploaderRef>,
+    onuploaderror: Callback<UploadError>,
+    paste_links: bool,
+    html_converter: Option<Callback<String, String>>,
+    #[cfg(feature = "preview")]
+    preview_tabs: bool,
+    #[cfg(feature = "preview")]
+    preview_extensions: MarkdownExtensions,
+    #[cfg(feature = "preview")]
+    tab: EditorTab,
+    textarea: NodeRef,
+    history: History,
 }
 
 #[derive(Clone, Properties, PartialEq)]
@@ -43,12 +107,68 @@ pub struct SimpleEditorProps {
     #[prop_or(Some(SimpleToolbar::new().build()))]
     pub toolbar: Option<Html>,
 
+    #[prop_or_default]
+    pub controlled: bool,
+
     #[prop_or(Callback::noop())]
     pub oninput: Callback<InputEvent>,
+
+    #[prop_or(Callback::noop())]
+    pub onchange: Callback<String>,
+
+    #[prop_or(Callback::noop())]
+    pub onselectionchange: Callback<Selection>,
+
+    #[prop_or_default]
+    pub keymap: Option<Keymap>,
+
+    #[prop_or(true)]
+    pub continue_lists: bool,
+
+    #[prop_or(true)]
+    pub renumber_lists: bool,
+
+    #[prop_or(Some(IndentStyle::default()))]
+    pub indent: Option<IndentStyle>,
+
+    #[prop_or(100)]
+    pub history_limit: usize,
+
+    #[prop_or(Callback::noop())]
+    pub ondrop: Callback<DragEvent>,
+
+    #[prop_or(Callback::noop())]
+    pub ondragover: Callback<DragEvent>,
+
+    #[prop_or(Callback::noop())]
+    pub onpaste: Callback<Event>,
+
+    #[prop_or_default]
+    pub onfiles: Option<Callback<Vec<File>>>,
+
+    #[prop_or_default]
+    pub uploader: Option<UploaderRef>,
+
+    #[prop_or(Callback::noop())]
+    pub onuploaderror: Callback<UploadError>,
+
+    #[prop_or(true)]
+    pub paste_links: bool,
+
+    #[prop_or_else(default_html_converter)]
+    pub html_converter: Option<Callback<String, String>>,
+
+    #[cfg(feature = "preview")]
+    #[prop_or_default]
+    pub preview_tabs: bool,
+
+    #[cfg(feature = "preview")]
+    #[prop_or_default]
+    pub preview_extensions: MarkdownExtensions,
 }
 
 impl Component for SimpleEditor {
-    type Message = ();
+    type Message = SimpleEditorMsg;
     type Properties = SimpleEditorProps;
 
     fn create(ctx: &Context<Self>) -> Self {
@@ -61,11 +181,101 @@ impl Component for SimpleEditor {
             placeholder: ctx.props().placeholder.clone(),
             text: ctx.props().text.clone(),
             toolbar: ctx.props().toolbar.clone(),
+            controlled: ctx.props().controlled,
             oninput: ctx.props().oninput.clone(),
+            onchange: ctx.props().onchange.clone(),
+            onselectionchange: ctx.props().onselectionchange.clone(),
+            keymap: ctx.props().keymap.clone(),
+            toolbar_keymap: Default::default(),
+            continue_lists: ctx.props().continue_lists,
+            renumber_lists: ctx.props().renumber_lists,
+            indent: ctx.props().indent,
+            ondrop: ctx.props().ondrop.clone(),
+            ondragover: ctx.props().ondragover.clone(),
+            onpaste: ctx.props().onpaste.clone(),
+            onfiles: ctx.props().onfiles.clone(),
+            uploader: ctx.props().uploader.clone(),
+            onuploaderror: ctx.props().onuploaderror.clone(),
+            paste_links: ctx.props().paste_links,
+            html_converter: ctx.props().html_converter.clone(),
+            #[cfg(feature = "preview")]
+            preview_tabs: ctx.props().preview_tabs,
+            #[cfg(feature = "preview")]
+            preview_extensions: ctx.props().preview_extensions,
+            #[cfg(feature = "preview")]
+            tab: EditorTab::Write,
+            textarea: NodeRef::default(),
+            history: History::new(
+                EditorState::new(ctx.props().text.clone(), 0..0),
+                ctx.props().history_limit,
+            ),
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        let Some(textarea) = self.textarea.cast::<HtmlTextAreaElement>() else {
+            return false;
+        };
+
+        let selection = self.history.current().selection;
+        let mut should_render = false;
+        match msg {
+            SimpleEditorMsg::Input(kind) => {
+                let mut state = EditorState::from_textarea(&textarea);
+                if self.renumber_lists && list::renumber_lists(&mut state) {
+                    state.write_to_textarea(&textarea);
+                }
+                self.onchange.emit(state.text.clone());
+                self.history.record(kind, state);
+                should_render = self.controlled;
+            },
+            SimpleEditorMsg::Select => self.history.select(EditorState::from_textarea(&textarea)),
+            SimpleEditorMsg::Undo => {
+                if let Some(state) = self.history.undo() {
+                    state.apply_to_textarea(&textarea);
+                }
+            },
+            SimpleEditorMsg::Redo => {
+                if let Some(state) = self.history.redo() {
+                    state.apply_to_textarea(&textarea);
+                }
+            },
+            SimpleEditorMsg::Apply(state) => state.apply_to_textarea(&textarea),
+            SimpleEditorMsg::Files(files) => {
+                if let Some(onfiles) = &self.onfiles {
+                    onfiles.emit(files);
+                } else if let Some(uploader) = &self.uploader {
+                    upload_files(textarea, files, uploader.clone(), self.onuploaderror.clone());
+                }
+            },
+            #[cfg(feature = "preview")]
+            SimpleEditorMsg::SelectTab(tab) => {
+                self.history.select(EditorState::from_textarea(&textarea));
+                let changed = self.tab != tab;
+                self.tab = tab;
+                return changed;
+            },
+            #[cfg(feature = "preview")]
+            SimpleEditorMsg::ToggleTask(pos) => {
+                let mut state = EditorState::from_textarea(&textarea);
+                if !list::toggle_task_at(&mut state, pos) {
+                    return false;
+                }
+                state.write_to_textarea(&textarea);
+                self.onchange.emit(state.text.clone());
+                self.history.record(EditKind::Format, state);
+                return true;
+            },
         }
+
+        let current_selection = self.history.current().selection;
+        if current_selection != selection {
+            self.onselectionchange.emit(current_selection);
+        }
+        should_render
     }
 
-    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
         let SimpleEditorProps {
             id,
             class,
@@ -75,7 +285,27 @@ impl Component for SimpleEditor {
             placeholder,
             text,
             toolbar,
+            controlled,
             oninput,
+            onchange,
+            onselectionchange,
+            keymap,
+            continue_lists,
+            renumber_lists,
+            indent,
+            history_limit,
+            ondrop,
+            ondragover,
+            onpaste,
+            onfiles,
+            uploader,
+            onuploaderror,
+            paste_links,
+            html_converter,
+            #[cfg(feature = "preview")]
+            preview_tabs,
+            #[cfg(feature = "preview")]
+            preview_extensions,
         } = ctx.props().clone();
 
         self.id = id;
@@ -86,19 +316,311 @@ impl Component for SimpleEditor {
         self.placeholder = placeholder;
         self.text = text;
         self.toolbar = toolbar;
+        self.controlled = controlled;
         self.oninput = oninput;
+        self.onchange = onchange;
+        self.onselectionchange = onselectionchange;
+        self.keymap = keymap;
+        self.continue_lists = continue_lists;
+        self.renumber_lists = renumber_lists;
+        self.indent = indent;
+        if history_limit != old_props.history_limit {
+            self.history.set_limit(history_limit);
+        }
+        self.ondrop = ondrop;
+        self.ondragover = ondragover;
+        self.onpaste = onpaste;
+        self.onfiles = onfiles;
+        self.uploader = uploader;
+        self.onuploaderror = onuploaderror;
+        self.paste_links = paste_links;
+        self.html_converter = html_converter;
+        #[cfg(feature = "preview")]
+        {
+            self.preview_tabs = preview_tabs;
+            self.preview_extensions = preview_extensions;
+        }
         true
     }
 
-    fn view(&self, _ctx: &Context<Self>) -> Html {
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        if !self.controlled {
+            return;
+        }
+
+        if let Some(textarea) = self.textarea.cast::<HtmlTextAreaElement>() {
+            if textarea.value() != self.text {
+                let selection = EditorState::from_textarea(&textarea).selection;
+                let state = EditorState::new(self.text.clone(), selection.clamp(self.text.chars().count()));
+                state.write_to_textarea(&textarea);
+                self.history.select(state);
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let oninput = {
+            let oninput = self.oninput.clone();
+            ctx.link().callback(move |event: InputEvent| {
+                let kind = edit_kind(&event);
+                oninput.emit(event);
+                SimpleEditorMsg::Input(kind)
+            })
+        };
+        let keymap = self.keymap.clone();
+        let toolbar_keymap = self.toolbar_keymap.clone();
+        let continue_lists = self.continue_lists;
+        let indent = self.indent;
+        let onkeydown = ctx.link().batch_callback(move |event: KeyboardEvent| {
+            let msg = history_shortcut(&event).or_else(|| {
+                let action = match &keymap {
+                    Some(keymap) => keymap.find(&event),
+                    None => toolbar_keymap.borrow().find(&event),
+                };
+                if let Some(action) = action {
+                    edit_on_key(&event, |state| {
+                        action(state);
+                        true
+                    })
+                } else if continue_lists && is_plain_key(&event, "Enter") {
+                    edit_on_key(&event, list::continue_list)
+                } else if let Some(fmt) = indent.and_then(|indent| indent_fmt(&event, indent)) {
+                    edit_on_key(&event, |state| {
+                        state.replace(fmt, UnselectedApplyMode::Line);
+                        true
+                    })
+                } else {
+                    None
+                }
+            });
+            if msg.is_some() {
+                event.prevent_default();
+            }
+            msg
+        });
+        let onkeyup = ctx.link().callback(|_: KeyboardEvent| SimpleEditorMsg::Select);
+        let onmouseup = ctx.link().callback(|_: MouseEvent| SimpleEditorMsg::Select);
+        let onselect = ctx.link().callback(|_: Event| SimpleEditorMsg::Select);
+
+        let handles_files = self.onfiles.is_some() || self.uploader.is_some();
+        let ondragover = {
+            let ondragover = self.ondragover.clone();
+            Callback::from(move |event: DragEvent| {
+                if handles_files && event.data_transfer().is_some_and(|data| has_files(&data)) {
+                    event.prevent_default();
+                }
+                ondragover.emit(event);
+            })
+        };
+        let ondrop = {
+            let ondrop = self.ondrop.clone();
+            ctx.link().batch_callback(move |event: DragEvent| {
+                let files = event.data_transfer().map(|data| files(&data)).unwrap_or_default();
+                let msg = (handles_files && !files.is_empty()).then(|| {
+                    event.prevent_default();
+                    SimpleEditorMsg::Files(files)
+                });
+                ondrop.emit(event);
+                msg
+            })
+        };
+        let onpaste = {
+            let onpaste = self.onpaste.clone();
+            let paste_links = self.paste_links;
+            let html_converter = self.html_converter.clone();
+            ctx.link().batch_callback(move |event: Event| {
+                let msg = event
+                    .dyn_ref::<ClipboardEvent>()
+                    .and_then(ClipboardEvent::clipboard_data)
+                    .and_then(|data| paste_msg(&event, &data, handles_files, paste_links, html_converter.as_ref()));
+                if msg.is_some() {
+                    event.prevent_default();
+                }
+                onpaste.emit(event);
+                msg
+            })
+        };
+
+        let is_writing = self.is_writing();
         html! {
             <div id = { self.id.clone() } class = { self.class.clone() }>
-                { self.toolbar.as_ref().cloned().unwrap_or(html! {}) }
-                <textarea cols = { self.cols.to_string() } rows = { self.rows.to_string() } class = "lew-simple__textarea"
-                        name = { self.name.clone() } placeholder = { self.placeholder.clone() } oninput = { self.oninput.clone() }>
+                { self.view_tabs(ctx) }
+                if is_writing {
+                    <ContextProvider<EditorContext> context = { EditorContext::new(self.textarea.clone()).with_keymap(self.toolbar_keymap.clone()) }>
+                        { self.toolbar.as_ref().cloned().unwrap_or(html! {}) }
+                    </ContextProvider<EditorContext>>
+                }
+                <textarea ref = { self.textarea.clone() } cols = { self.cols.to_string() } rows = { self.rows.to_string() }
+                        class = "lew-simple__textarea" name = { self.name.clone() } placeholder = { self.placeholder.clone() }
+                        hidden = { !is_writing } { oninput } { onkeydown } { onkeyup } { onmouseup } { onselect }
+                        { ondragover } { ondrop } { onpaste }>
                     { &self.text }
                 </textarea>
+                { self.view_preview(ctx) }
             </div>
         }
     }
 }
+
+impl SimpleEditor {
+    #[cfg(feature = "preview")]
+    fn is_writing(&self) -> bool {
+        !self.preview_tabs || self.tab == EditorTab::Write
+    }
+
+    #[cfg(not(feature = "preview"))]
+    fn is_writing(&self) -> bool {
+        true
+    }
+
+    #[cfg(feature = "preview")]
+    fn view_tabs(&self, ctx: &Context<Self>) -> Html {
+        if !self.preview_tabs {
+            return html! {};
+        }
+
+        let tab_class = |tab| {
+            if self.tab == tab {
+                "lew-simple__tab lew-simple__tab_active"
+            } else {
+                "lew-simple__tab"
+            }
+        };
+        html! {
+            <div class = "lew-simple__tabs">
+                <button type = "button" class = { tab_class(EditorTab::Write) }
+                        onclick = { ctx.link().callback(|_: MouseEvent| SimpleEditorMsg::SelectTab(EditorTab::Write)) }>
+                    { "Write" }
+                </button>
+                <button type = "button" class = { tab_class(EditorTab::Preview) }
+                        onclick = { ctx.link().callback(|_: MouseEvent| SimpleEditorMsg::SelectTab(EditorTab::Preview)) }>
+                    { "Preview" }
+                </button>
+            </div>
+        }
+    }
+
+    #[cfg(not(feature = "preview"))]
+    fn view_tabs(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+
+    #[cfg(feature = "preview")]
+    fn view_preview(&self, ctx: &Context<Self>) -> Html {
+        if self.is_writing() {
+            return html! {};
+        }
+
+        html! {
+            <MarkdownPreview class = "lew-simple__preview" text = { self.history.current().text.clone() }
+                    extensions = { self.preview_extensions } debounce_ms = { 0 }
+                    ontoggletask = { ctx.link().callback(SimpleEditorMsg::ToggleTask) } />
+        }
+    }
+
+    #[cfg(not(feature = "preview"))]
+    fn view_preview(&self, _ctx: &Context<Self>) -> Html {
+        html! {}
+    }
+}
+
+fn edit_kind(event: &InputEvent) -> EditKind {
+    let event: &Event = event.as_ref();
+    match event.dyn_ref::<InputEvent>().map(InputEvent::input_type).as_deref() {
+        Some("insertText") => EditKind::Insert,
+        Some("deleteContentBackward" | "deleteContentForward") => EditKind::Delete,
+        _ => EditKind::Format,
+    }
+}
+
+fn has_files(data: &DataTransfer) -> bool {
+    data.files().is_some_and(|files| files.length() > 0)
+}
+
+fn files(data: &DataTransfer) -> Vec<File> {
+    data.files()
+        .map(|files| (0..files.length()).filter_map(|index| files.get(index)).collect())
+        .unwrap_or_default()
+}
+
+fn paste_msg(
+    event: &Event,
+    data: &DataTransfer,
+    handles_files: bool,
+    paste_links: bool,
+    html_converter: Option<&Callback<String, String>>,
+) -> Option<SimpleEditorMsg> {
+    let files = files(data);
+    if handles_files && !files.is_empty() {
+        return Some(SimpleEditorMsg::Files(files));
+    }
+
+    let textarea = event.target_dyn_into::<HtmlTextAreaElement>()?;
+    let mut state = EditorState::from_textarea(&textarea);
+    let text = data.get_data("text/plain").unwrap_or_default();
+    let selection = state.char_selection();
+    if paste_links && !selection.is_empty() && looks_like_url(&text) {
+        let draft = LinkDraft {
+            text: state.selected_text(),
+            url: validate_url(&text, DEFAULT_LINK_SCHEMES).ok()?,
+            title: String::new(),
+            range: selection,
+        };
+        apply_link(&mut state, &draft);
+        return Some(SimpleEditorMsg::Apply(state));
+    }
+
+    let html = data.get_data("text/html").unwrap_or_default();
+    let markdown = html_converter.filter(|_| !html.trim().is_empty())?.emit(html);
+    if markdown.is_empty() {
+        return None;
+    }
+    state.insert_text(&markdown);
+    Some(SimpleEditorMsg::Apply(state))
+}
+
+pub fn default_html_converter() -> Option<Callback<String, String>> {
+    Some(Callback::from(|html: String| {
+        if is_rich_html(&html) {
+            html_to_markdown(&html)
+        } else {
+            String::new()
+        }
+    }))
+}
+
+fn is_plain_key(event: &KeyboardEvent, key: &str) -> bool {
+    event.key() == key
+        && !(event.ctrl_key() || event.meta_key() || event.alt_key() || event.shift_key() || event.is_composing())
+}
+
+fn indent_fmt(event: &KeyboardEvent, indent: IndentStyle) -> Option<ReplaceFmt> {
+    if event.key() != "Tab" || event.ctrl_key() || event.meta_key() || event.alt_key() || event.is_composing() {
+        return None;
+    }
+
+    Some(if event.shift_key() {
+        ReplaceFmt::Outdent(indent.unit())
+    } else {
+        ReplaceFmt::Indent(indent.unit())
+    })
+}
+
+fn edit_on_key(event: &KeyboardEvent, edit: impl FnOnce(&mut EditorState) -> bool) -> Option<SimpleEditorMsg> {
+    let textarea = event.target_dyn_into::<HtmlTextAreaElement>()?;
+    let mut state = EditorState::from_textarea(&textarea);
+    edit(&mut state).then_some(SimpleEditorMsg::Apply(state))
+}
+
+fn history_shortcut(event: &KeyboardEvent) -> Option<SimpleEditorMsg> {
+    if !(event.ctrl_key() || event.meta_key()) || event.alt_key() {
+        return None;
+    }
+
+    match event.key().to_lowercase().as_str() {
+        "z" if event.shift_key() => Some(SimpleEditorMsg::Redo),
+        "z" => Some(SimpleEditorMsg::Undo),
+        "y" if !event.shift_key() => Some(SimpleEditorMsg::Redo),
+        _ => None,
+    }
+}
@@ -0,0 +1,237 @@
+use crate::toolbar::EditorState;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EditKind {
+    Insert,
+    Delete,
+    Format,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    pub kind: EditKind,
+    pub before: EditorState,
+    pub after: EditorState,
+}
+
+impl Edit {
+    fn can_merge(&self, kind: EditKind, before: &EditorState, after: &EditorState) -> bool {
+        kind != EditKind::Format && self.kind == kind && self.after == *before && !starts_word(kind, before, after)
+    }
+}
+
+fn starts_word(kind: EditKind, before: &EditorState, after: &EditorState) -> bool {
+    let is_whitespace = |ch: Option<char>| ch.is_some_and(char::is_whitespace);
+    match kind {
+        EditKind::Insert => {
+            let pos = before.char_selection().start;
+            let previous = pos.checked_sub(1).and_then(|pos| before.text.chars().nth(pos));
+            is_whitespace(previous) && !is_whitespace(after.text.chars().nth(pos))
+        },
+        EditKind::Delete => is_whitespace(before.text.chars().nth(after.char_selection().start)),
+        EditKind::Format => true,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    current: EditorState,
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    limit: usize,
+}
+
+impl History {
+    pub fn new(state: EditorState, limit: usize) -> Self {
+        Self {
+            current: state,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    pub fn current(&self) -> &EditorState {
+        &self.current
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.truncate();
+    }
+
+    pub fn reset(&mut self, state: EditorState) {
+        self.current = state;
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn select(&mut self, state: EditorState) {
+        if state.text == self.current.text {
+            self.current.selection = state.selection;
+        } else {
+            self.record(EditKind::Format, state);
+        }
+    }
+
+    pub fn record(&mut self, kind: EditKind, after: EditorState) {
+        if after.text == self.current.text {
+            self.current = after;
+            return;
+        }
+
+        let before = std::mem::replace(&mut self.current, after.clone());
+        self.redo.clear();
+        match self.undo.last_mut() {
+            Some(edit) if edit.can_merge(kind, &before, &after) => edit.after = after,
+            _ => {
+                self.undo.push(Edit { kind, before, after });
+                self.truncate();
+            },
+        }
+    }
+
+    fn truncate(&mut self) {
+        let excess = self.undo.len().saturating_sub(self.limit);
+        self.undo.drain(..excess);
+    }
+
+    pub fn undo(&mut self) -> Option<&EditorState> {
+        let edit = self.undo.pop()?;
+        self.current = edit.before.clone();
+        self.redo.push(edit);
+        Some(&self.current)
+    }
+
+    pub fn redo(&mut self) -> Option<&EditorState> {
+        let edit = self.redo.pop()?;
+        self.current = edit.after.clone();
+        self.undo.push(edit);
+        Some(&self.current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toolbar::Selection;
+
+    fn state(text: &str) -> EditorState {
+        let len = text.chars().count();
+        EditorState::new(text, len..len)
+    }
+
+    #[test]
+    fn record_merges_consecutive_typing() {
+        let mut history = History::new(state(""), 10);
+        history.record(EditKind::Insert, state("a"));
+        history.record(EditKind::Insert, state("ab"));
+        assert_eq!(history.undo(), Some(&state("")));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn record_splits_at_whitespace_and_kind_changes() {
+        let mut history = History::new(state(""), 10);
+        history.record(EditKind::Insert, state("a "));
+        history.record(EditKind::Insert, state("a b"));
+        history.record(EditKind::Delete, state("a "));
+        history.record(EditKind::Format, state("**a** "));
+        assert_eq!(history.undo(), Some(&state("a ")));
+        assert_eq!(history.undo(), Some(&state("a b")));
+        assert_eq!(history.undo(), Some(&state("a ")));
+        assert_eq!(history.undo(), Some(&state("")));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn record_merges_typing_before_a_trailing_newline() {
+        let mut history = History::new(EditorState::new("\n", 0..0), 10);
+        for (text, caret) in [("a\n", 1), ("ab\n", 2), ("abc\n", 3)] {
+            history.record(EditKind::Insert, EditorState::new(text, caret..caret));
+        }
+        assert_eq!(history.undo(), Some(&EditorState::new("\n", 0..0)));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn record_splits_words_typed_mid_document() {
+        let mut history = History::new(EditorState::new("xy", 1..1), 10);
+        for (text, caret) in [("xay", 2), ("xa y", 3), ("xa by", 4)] {
+            history.record(EditKind::Insert, EditorState::new(text, caret..caret));
+        }
+        assert_eq!(history.undo(), Some(&EditorState::new("xa y", 3..3)));
+        assert_eq!(history.undo(), Some(&EditorState::new("xy", 1..1)));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn record_splits_deletes_at_whitespace() {
+        let mut history = History::new(EditorState::new("a b", 3..3), 10);
+        for (text, caret) in [("a ", 2), ("a", 1), ("", 0)] {
+            history.record(EditKind::Delete, EditorState::new(text, caret..caret));
+        }
+        assert_eq!(history.undo(), Some(&EditorState::new("a ", 2..2)));
+        assert_eq!(history.undo(), Some(&EditorState::new("a b", 3..3)));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    fn record_updates_selection_without_an_edit() {
+        let mut history = History::new(state("a"), 10);
+        history.record(EditKind::Format, state("*a*"));
+        history.undo();
+        history.record(EditKind::Format, EditorState::new("a", 0..1));
+        assert!(history.can_redo());
+        assert!(!history.can_undo());
+        assert_eq!(history.current().selection, Selection::new(0, 1));
+    }
+
+    #[test]
+    fn record_clears_redo() {
+        let mut history = History::new(state("a"), 10);
+        history.record(EditKind::Format, state("*a*"));
+        history.undo();
+        history.record(EditKind::Format, state("**a**"));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn record_drops_oldest_edits_over_limit() {
+        let mut history = History::new(state(""), 2);
+        for text in ["a", "a b", "a b c"] {
+            history.record(EditKind::Format, state(text));
+        }
+        assert_eq!(history.undo(), Some(&state("a b")));
+        assert_eq!(history.undo(), Some(&state("a")));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn set_limit_drops_oldest_edits() {
+        let mut history = History::new(state(""), 10);
+        for text in ["a", "a b", "a b c"] {
+            history.record(EditKind::Format, state(text));
+        }
+        history.set_limit(1);
+        assert_eq!(history.undo(), Some(&state("a b")));
+        assert_eq!(history.undo(), None);
+    }
+
+    #[test]
+    fn redo_reapplies_undone_edit() {
+        let mut history = History::new(state(""), 10);
+        history.record(EditKind::Format, state("a"));
+        history.undo();
+        assert_eq!(history.redo(), Some(&state("a")));
+        assert_eq!(history.redo(), None);
+    }
+}
@@ -0,0 +1,194 @@
+use std::fmt;
+use std::rc::Rc;
+
+use yew::KeyboardEvent;
+
+use crate::toolbar::EditorState;
+
+pub type Action = Rc<dyn Fn(&mut EditorState)>;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Shortcut {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into().to_lowercase(),
+            ..Self::default()
+        }
+    }
+
+    pub fn ctrl(key: impl Into<String>) -> Self {
+        Self::new(key).with_ctrl()
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn matches(&self, event: &KeyboardEvent) -> bool {
+        self.ctrl == (event.ctrl_key() || event.meta_key())
+            && self.shift == event.shift_key()
+            && self.alt == event.alt_key()
+            && self.matches_key(&event.key(), &event.code())
+    }
+
+    pub fn matches_key(&self, key: &str, code: &str) -> bool {
+        let key = key.to_lowercase();
+        if key == self.key {
+            return true;
+        }
+
+        let is_layout_key = key.chars().all(|ch| ch.is_ascii_alphanumeric());
+        !is_layout_key && key_code(&self.key).as_deref() == Some(code)
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+
+        let mut chars = self.key.chars();
+        if let Some(first) = chars.next() {
+            write!(f, "{}{}", first.to_uppercase(), chars.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+fn key_code(key: &str) -> Option<String> {
+    let mut chars = key.chars();
+    let ch = chars.next()?;
+    if chars.next().is_some() {
+        return None;
+    }
+
+    match ch {
+        'a'..='z' => Some(format!("Key{}", ch.to_ascii_uppercase())),
+        '0'..='9' => Some(format!("Digit{ch}")),
+        '.' => Some("Period".to_string()),
+        ',' => Some("Comma".to_string()),
+        '/' => Some("Slash".to_string()),
+        ';' => Some("Semicolon".to_string()),
+        '-' => Some("Minus".to_string()),
+        '=' => Some("Equal".to_string()),
+        '[' => Some("BracketLeft".to_string()),
+        ']' => Some("BracketRight".to_string()),
+        '`' => Some("Backquote".to_string()),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Keymap {
+    bindings: Vec<(Shortcut, Action)>,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn bind(mut self, shortcut: Shortcut, action: impl Fn(&mut EditorState) + 'static) -> Self {
+        self.insert(shortcut, Rc::new(action));
+        self
+    }
+
+    pub fn unbind(mut self, shortcut: &Shortcut) -> Self {
+        self.bindings.retain(|(bound, _)| bound != shortcut);
+        self
+    }
+
+    pub fn insert(&mut self, shortcut: Shortcut, action: Action) {
+        match self.bindings.iter_mut().find(|(bound, _)| *bound == shortcut) {
+            Some((_, bound_action)) => *bound_action = action,
+            None => self.bindings.push((shortcut, action)),
+        }
+    }
+
+    pub fn shortcuts(&self) -> impl Iterator<Item = &Shortcut> {
+        self.bindings.iter().map(|(shortcut, _)| shortcut)
+    }
+
+    pub fn find(&self, event: &KeyboardEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(shortcut, _)| shortcut.matches(event))
+            .map(|(_, action)| action.clone())
+    }
+}
+
+impl PartialEq for Keymap {
+    fn eq(&self, other: &Self) -> bool {
+        self.bindings.len() == other.bindings.len()
+            && self
+                .bindings
+                .iter()
+                .zip(&other.bindings)
+                .all(|((shortcut, action), (other_shortcut, other_action))| {
+                    shortcut == other_shortcut && Rc::ptr_eq(action, other_action)
+                })
+    }
+}
+
+impl fmt::Debug for Keymap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.shortcuts()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_printed_key() {
+        assert!(Shortcut::ctrl("b").matches_key("b", "KeyB"));
+        assert!(Shortcut::ctrl("b").matches_key("B", "KeyB"));
+        assert!(!Shortcut::ctrl("b").matches_key("i", "KeyI"));
+    }
+
+    #[test]
+    fn dvorak_letters_do_not_fall_back_to_physical_keys() {
+        assert!(!Shortcut::ctrl("i").matches_key("c", "KeyI"));
+        assert!(!Shortcut::ctrl("b").matches_key("x", "KeyB"));
+        assert!(Shortcut::ctrl("c").matches_key("c", "KeyI"));
+    }
+
+    #[test]
+    fn non_latin_layouts_fall_back_to_physical_keys() {
+        assert!(Shortcut::ctrl("b").matches_key("и", "KeyB"));
+        assert!(Shortcut::ctrl("i").matches_key("ш", "KeyI"));
+        assert!(!Shortcut::ctrl("i").matches_key("и", "KeyB"));
+    }
+
+    #[test]
+    fn shifted_symbols_fall_back_to_physical_keys() {
+        assert!(Shortcut::ctrl("8").with_shift().matches_key("*", "Digit8"));
+        assert!(Shortcut::ctrl(".").with_shift().matches_key(">", "Period"));
+    }
+}
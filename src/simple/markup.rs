@@ -0,0 +1,270 @@
+pub use self::markdown::{html_to_markdown, is_rich_html};
+
+pub mod markdown;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Text(String),
+    Start(String, Vec<(String, String)>),
+    End(String),
+    Other,
+}
+
+impl Token {
+    pub fn parse(input: &str) -> Option<(Self, usize)> {
+        if let Some(comment) = input.strip_prefix("<!--") {
+            let end = comment.find("-->").map(|pos| pos + 3).unwrap_or(comment.len());
+            return Some((Token::Other, 4 + end));
+        }
+        if input.starts_with("<!") || input.starts_with("<?") {
+            let end = input.find('>').map(|pos| pos + 1).unwrap_or(input.len());
+            return Some((Token::Other, end));
+        }
+
+        let (is_end, body) = match input.strip_prefix("</") {
+            Some(body) => (true, body),
+            None => (false, input.strip_prefix('<')?),
+        };
+        if !body.starts_with(|ch: char| ch.is_ascii_alphabetic()) {
+            return None;
+        }
+
+        let name_len = body
+            .find(|ch: char| !(ch.is_ascii_alphanumeric() || ch == '-'))
+            .unwrap_or(body.len());
+        let name = body[..name_len].to_ascii_lowercase();
+        let (attributes, attributes_len) = parse_attributes(&body[name_len..]);
+        let consumed = input.len() - body.len() + name_len + attributes_len;
+
+        if is_end {
+            Some((Token::End(name), consumed))
+        } else {
+            Some((Token::Start(name, attributes), consumed))
+        }
+    }
+}
+
+pub fn tokenize(html: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut text = String::new();
+    let mut rest = html;
+
+    while let Some(pos) = rest.find('<') {
+        text.push_str(&rest[..pos]);
+        rest = &rest[pos..];
+
+        let Some((token, consumed)) = Token::parse(rest) else {
+            text.push('<');
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[consumed..];
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(std::mem::take(&mut text)));
+        }
+        tokens.push(token);
+    }
+
+    text.push_str(rest);
+    if !text.is_empty() {
+        tokens.push(Token::Text(text));
+    }
+    tokens
+}
+
+pub fn parse_attributes(input: &str) -> (Vec<(String, String)>, usize) {
+    let mut attributes = Vec::new();
+    let mut pos = 0;
+    let bytes = input.as_bytes();
+
+    while pos < bytes.len() {
+        match bytes[pos] {
+            b'>' => return (attributes, pos + 1),
+            b'/' => pos += 1,
+            ch if ch.is_ascii_whitespace() => pos += 1,
+            _ => {
+                let name_end = input[pos..]
+                    .find(|ch: char| ch.is_ascii_whitespace() || matches!(ch, '=' | '>' | '/'))
+                    .map(|end| pos + end)
+                    .unwrap_or(input.len());
+                let name = input[pos..name_end].to_ascii_lowercase();
+                pos = name_end;
+                while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                    pos += 1;
+                }
+
+                let mut value = String::new();
+                if pos < bytes.len() && bytes[pos] == b'=' {
+                    pos += 1;
+                    while pos < bytes.len() && bytes[pos].is_ascii_whitespace() {
+                        pos += 1;
+                    }
+                    match bytes.get(pos) {
+                        Some(&quote @ (b'"' | b'\'')) => {
+                            let value_end = input[pos + 1..]
+                                .find(quote as char)
+                                .map(|end| pos + 1 + end)
+                                .unwrap_or(input.len());
+                            value = input[pos + 1..value_end].to_string();
+                            pos = (value_end + 1).min(input.len());
+                        },
+                        _ => {
+                            let value_end = input[pos..]
+                                .find(|ch: char| ch.is_ascii_whitespace() || ch == '>')
+                                .map(|end| pos + end)
+                                .unwrap_or(input.len());
+                            value = input[pos..value_end].to_string();
+                            pos = value_end;
+                        },
+                    }
+                }
+                if !name.is_empty() {
+                    attributes.push((name, value));
+                }
+            },
+        }
+    }
+    (attributes, input.len())
+}
+
+pub fn decode_entities(value: &str) -> String {
+    let mut output = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(pos) = rest.find('&') {
+        output.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+
+        let (decoded, consumed) = if let Some(numeric) = rest.strip_prefix('#') {
+            let (radix, digits_start) = if numeric.starts_with(['x', 'X']) {
+                (16, 2)
+            } else {
+                (10, 1)
+            };
+            let digits_len = rest[digits_start..]
+                .find(|ch: char| !ch.is_digit(radix))
+                .unwrap_or(rest.len() - digits_start);
+            let digits = &rest[digits_start..digits_start + digits_len];
+            let decoded = u32::from_str_radix(digits, radix)
+                .ok()
+                .map(|code| char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            let end = digits_start + digits_len;
+            (decoded, if rest[end..].starts_with(';') { end + 1 } else { end })
+        } else {
+            let name_len = rest.find(|ch: char| !ch.is_ascii_alphanumeric()).unwrap_or(rest.len());
+            let decoded = named_entity(&rest[..name_len]);
+            (
+                decoded,
+                if rest[name_len..].starts_with(';') {
+                    name_len + 1
+                } else {
+                    name_len
+                },
+            )
+        };
+
+        match decoded {
+            Some(ch) => {
+                output.push(ch);
+                rest = &rest[consumed..];
+            },
+            None => output.push('&'),
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+pub fn normalize_url(url: &str) -> String {
+    decode_entities(url)
+        .chars()
+        .filter(|ch| !ch.is_whitespace() && !ch.is_control())
+        .collect()
+}
+
+fn named_entity(name: &str) -> Option<char> {
+    let ch = match name {
+        "amp" => '&',
+        "lt" => '<',
+        "gt" => '>',
+        "quot" => '"',
+        "apos" => '\'',
+        "colon" => ':',
+        "Tab" => '\t',
+        "NewLine" => '\n',
+        "nbsp" => '\u{a0}',
+        "shy" => '\u{ad}',
+        "copy" => '©',
+        "reg" => '®',
+        "trade" => '™',
+        "deg" => '°',
+        "middot" => '·',
+        "bull" => '•',
+        "hellip" => '…',
+        "ndash" => '–',
+        "mdash" => '—',
+        "lsquo" => '‘',
+        "rsquo" => '’',
+        "ldquo" => '“',
+        "rdquo" => '”',
+        "laquo" => '«',
+        "raquo" => '»',
+        "times" => '×',
+        "euro" => '€',
+        _ => return None,
+    };
+    Some(ch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn start(name: &str, attributes: &[(&str, &str)]) -> Token {
+        Token::Start(
+            name.to_string(),
+            attributes
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn tokenize_reads_tags_and_attributes() {
+        assert_eq!(
+            tokenize("<A HREF=x title='y z' data-q=\"1\" hidden>t</a><!--c--><br/>"),
+            vec![
+                start("a", &[("href", "x"), ("title", "y z"), ("data-q", "1"), ("hidden", "")]),
+                Token::Text("t".to_string()),
+                Token::End("a".to_string()),
+                Token::Other,
+                start("br", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn tokenize_keeps_stray_angle_brackets_as_text() {
+        assert_eq!(
+            tokenize("a < b <1 <p"),
+            vec![Token::Text("a < b <1 ".to_string()), start("p", &[])]
+        );
+        assert_eq!(tokenize("<!-- open"), vec![Token::Other]);
+        assert_eq!(tokenize("<a title=\"x"), vec![start("a", &[("title", "x")])]);
+    }
+
+    #[test]
+    fn decode_entities_handles_named_and_numeric_references() {
+        assert_eq!(decode_entities("&lt;&amp;amp;&#x26;&#38&nbsp;"), "<&amp;&&\u{a0}");
+        assert_eq!(decode_entities("&unknown; &#xZ; & x"), "&unknown; &#xZ; & x");
+        assert_eq!(decode_entities("&#1114112;"), "\u{fffd}");
+    }
+
+    #[test]
+    fn normalize_url_strips_entities_and_invisible_characters() {
+        assert_eq!(normalize_url(" java\tscript&#58;x "), "javascript:x");
+        assert_eq!(normalize_url("&#106;ava\u{0}script:x"), "javascript:x");
+    }
+}
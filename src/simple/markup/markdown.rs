@@ -0,0 +1,828 @@
+use super::{decode_entities, tokenize, Token};
+use crate::toolbar::code::fence_for;
+use crate::toolbar::link::LinkDraft;
+use crate::toolbar::Selection;
+
+const VOID_TAGS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track", "wbr",
+];
+
+const SKIPPED_TAGS: &[&str] = &[
+    "head", "script", "style", "title", "template", "noscript", "iframe", "object", "svg", "math", "textarea",
+    "select", "button",
+];
+
+const BLOCK_TAGS: &[&str] = &[
+    "address",
+    "article",
+    "aside",
+    "blockquote",
+    "body",
+    "center",
+    "dd",
+    "details",
+    "dialog",
+    "div",
+    "dl",
+    "dt",
+    "fieldset",
+    "figcaption",
+    "figure",
+    "footer",
+    "form",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "header",
+    "hr",
+    "html",
+    "li",
+    "main",
+    "nav",
+    "ol",
+    "p",
+    "pre",
+    "section",
+    "summary",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "ul",
+];
+
+const RICH_TAGS: &[&str] = &[
+    "a",
+    "b",
+    "blockquote",
+    "code",
+    "del",
+    "em",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "i",
+    "img",
+    "li",
+    "mark",
+    "ol",
+    "pre",
+    "s",
+    "strike",
+    "strong",
+    "table",
+    "ul",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Node {
+    Text(String),
+    Element(Element),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Element {
+    name: String,
+    attributes: Vec<(String, String)>,
+    children: Vec<Node>,
+}
+
+impl Element {
+    fn new(name: impl Into<String>, attributes: Vec<(String, String)>) -> Self {
+        Self {
+            name: name.into(),
+            attributes,
+            children: Vec::new(),
+        }
+    }
+
+    fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn style(&self, property: &str) -> Option<String> {
+        self.attribute("style")?.split(';').find_map(|declaration| {
+            let (name, value) = declaration.split_once(':')?;
+            (name.trim().eq_ignore_ascii_case(property)).then(|| value.trim().to_ascii_lowercase())
+        })
+    }
+
+    fn is_block(&self) -> bool {
+        BLOCK_TAGS.contains(&self.name.as_str())
+    }
+
+    fn has_block(&self) -> bool {
+        self.children.iter().any(|child| match child {
+            Node::Element(element) => element.is_block() || element.has_block(),
+            Node::Text(_) => false,
+        })
+    }
+
+    fn is_checkbox(&self) -> bool {
+        self.name == "input"
+            && self
+                .attribute("type")
+                .is_some_and(|kind| kind.eq_ignore_ascii_case("checkbox"))
+    }
+
+    fn is_bold(&self) -> bool {
+        match self.name.as_str() {
+            "b" | "strong" => !self
+                .style("font-weight")
+                .is_some_and(|weight| weight == "normal" || weight.parse::<u32>().is_ok_and(|weight| weight < 600)),
+            _ => self
+                .style("font-weight")
+                .is_some_and(|weight| weight == "bold" || weight.parse::<u32>().is_ok_and(|weight| weight >= 600)),
+        }
+    }
+
+    fn is_italic(&self) -> bool {
+        match self.name.as_str() {
+            "i" | "em" => self.style("font-style").is_none_or(|style| style != "normal"),
+            _ => self.style("font-style").is_some_and(|style| style == "italic"),
+        }
+    }
+
+    fn text_content(&self, output: &mut String) {
+        for child in &self.children {
+            match child {
+                Node::Text(text) => output.push_str(text),
+                Node::Element(element) if element.name == "br" => output.push('\n'),
+                Node::Element(element) => element.text_content(output),
+            }
+        }
+    }
+
+    fn find(&self, predicate: &impl Fn(&Element) -> bool) -> Option<&Element> {
+        self.children.iter().find_map(|child| match child {
+            Node::Element(element) if predicate(element) => Some(element),
+            Node::Element(element) => element.find(predicate),
+            Node::Text(_) => None,
+        })
+    }
+}
+
+fn parse(html: &str) -> Element {
+    let mut stack = vec![Element::new("", Vec::new())];
+    let mut skipped: Option<String> = None;
+
+    for token in tokenize(html) {
+        if let Some(skipped_tag) = &skipped {
+            if matches!(&token, Token::End(name) if name == skipped_tag) {
+                skipped = None;
+            }
+            continue;
+        }
+
+        match token {
+            Token::Text(text) => stack
+                .last_mut()
+                .expect("Root element expected")
+                .children
+                .push(Node::Text(decode_entities(&text))),
+            Token::Start(name, _) if SKIPPED_TAGS.contains(&name.as_str()) => skipped = Some(name),
+            Token::Start(name, attributes) => {
+                close_implied(&mut stack, &name);
+                let element = Element::new(name, attributes);
+                if VOID_TAGS.contains(&element.name.as_str()) {
+                    stack
+                        .last_mut()
+                        .expect("Root element expected")
+                        .children
+                        .push(Node::Element(element));
+                } else {
+                    stack.push(element);
+                }
+            },
+            Token::End(name) => {
+                if let Some(pos) = stack.iter().skip(1).rposition(|element| element.name == name) {
+                    close_to(&mut stack, pos + 1);
+                }
+            },
+            Token::Other => (),
+        }
+    }
+
+    close_to(&mut stack, 1);
+    stack.pop().expect("Root element expected")
+}
+
+fn close_implied(stack: &mut Vec<Element>, name: &str) {
+    let (closed, scope): (&[&str], &[&str]) = match name {
+        "li" => (&["li"], &["ul", "ol"]),
+        "dt" | "dd" => (&["dt", "dd"], &["dl"]),
+        "tr" => (&["tr"], &["table"]),
+        "td" | "th" => (&["td", "th"], &["tr", "table"]),
+        "tbody" | "thead" | "tfoot" => (&["tbody", "thead", "tfoot"], &["table"]),
+        "p" => (&["p"], &[]),
+        name if BLOCK_TAGS.contains(&name) => (&["p"], &[]),
+        _ => return,
+    };
+
+    let inside = stack
+        .iter()
+        .rposition(|element| closed.contains(&element.name.as_str()))
+        .filter(|&pos| {
+            pos > 0
+                && !stack[pos + 1..]
+                    .iter()
+                    .any(|element| scope.contains(&element.name.as_str()))
+        });
+    if let Some(pos) = inside {
+        close_to(stack, pos);
+    }
+}
+
+fn close_to(stack: &mut Vec<Element>, len: usize) {
+    while stack.len() > len {
+        let element = stack.pop().expect("Element expected");
+        stack
+            .last_mut()
+            .expect("Parent element expected")
+            .children
+            .push(Node::Element(element));
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct InlineContext {
+    bold: bool,
+    italic: bool,
+    link: bool,
+}
+
+#[derive(Debug, Default)]
+struct Writer {
+    blocks: Vec<String>,
+    inline: String,
+}
+
+impl Writer {
+    fn write_nodes(&mut self, nodes: &[Node]) {
+        for node in nodes {
+            self.write_node(node);
+        }
+    }
+
+    fn write_node(&mut self, node: &Node) {
+        let element = match node {
+            Node::Text(text) => {
+                push_text(&mut self.inline, text);
+                return;
+            },
+            Node::Element(element) => element,
+        };
+
+        match element.name.as_str() {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                let level = element.name[1..].parse().unwrap_or(1);
+                let content = render_inline(&element.children, InlineContext::default());
+                let content = collapse_lines(&content);
+                if !content.is_empty() {
+                    self.blocks.push(format!("{} {content}", "#".repeat(level)));
+                }
+            },
+            "blockquote" => {
+                self.flush();
+                let content = render_blocks(&element.children);
+                if !content.is_empty() {
+                    self.blocks.push(prefix_lines(&content, "> ", ">"));
+                }
+            },
+            "pre" => {
+                self.flush();
+                self.blocks.push(render_code_block(element));
+            },
+            "ul" | "ol" => {
+                self.flush();
+                let list = render_list(element);
+                if !list.is_empty() {
+                    self.blocks.push(list);
+                }
+            },
+            "table" => {
+                self.flush();
+                let table = render_table(element);
+                if !table.is_empty() {
+                    self.blocks.push(table);
+                }
+            },
+            "hr" => {
+                self.flush();
+                self.blocks.push("---".to_string());
+            },
+            "br" => self.inline.push('\n'),
+            _ if element.is_block() || element.has_block() => {
+                if element.is_block() {
+                    self.flush();
+                }
+                self.write_nodes(&element.children);
+                if element.is_block() {
+                    self.flush();
+                }
+            },
+            _ => push_inline(&mut self.inline, &render_element(element, InlineContext::default())),
+        }
+    }
+
+    fn flush(&mut self) {
+        let inline = std::mem::take(&mut self.inline);
+        let lines: Vec<String> = inline.lines().map(|line| escape_line_start(line.trim())).collect();
+        for paragraph in lines.split(|line| line.is_empty()) {
+            if !paragraph.is_empty() {
+                self.blocks.push(paragraph.join("\\\n"));
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.flush();
+        self.blocks.join("\n\n")
+    }
+}
+
+pub fn html_to_markdown(html: &str) -> String {
+    render_blocks(&parse(html).children)
+}
+
+pub fn is_rich_html(html: &str) -> bool {
+    tokenize(html)
+        .iter()
+        .any(|token| matches!(token, Token::Start(name, _) if RICH_TAGS.contains(&name.as_str())))
+}
+
+fn render_blocks(nodes: &[Node]) -> String {
+    let mut writer = Writer::default();
+    writer.write_nodes(nodes);
+    writer.finish()
+}
+
+fn render_inline(nodes: &[Node], context: InlineContext) -> String {
+    let mut output = String::new();
+    for node in nodes {
+        match node {
+            Node::Text(text) => push_text(&mut output, text),
+            Node::Element(element) => push_inline(&mut output, &render_element(element, context)),
+        }
+    }
+    output
+}
+
+fn render_element(element: &Element, context: InlineContext) -> String {
+    match element.name.as_str() {
+        "br" => "\n".to_string(),
+        "img" => render_image(element),
+        "input" => String::new(),
+        "code" | "kbd" | "samp" | "tt" => {
+            let mut code = String::new();
+            element.text_content(&mut code);
+            code_span(&code)
+        },
+        "a" if !context.link => render_link(element, context),
+        "del" | "s" | "strike" => wrap(&render_inline(&element.children, context), "~~"),
+        "mark" => wrap(&render_inline(&element.children, context), "=="),
+        "sup" | "sub" | "ins" | "u" => {
+            let tag = if element.name == "u" {
+                "ins"
+            } else {
+                element.name.as_str()
+            };
+            let content = render_inline(&element.children, context);
+            if content.trim().is_empty() {
+                content
+            } else {
+                format!("<{tag}>{}</{tag}>", content.trim())
+            }
+        },
+        _ if element.is_bold() && !context.bold => {
+            let content = render_inline(&element.children, InlineContext { bold: true, ..context });
+            wrap(&content, "**")
+        },
+        _ if element.is_italic() && !context.italic => {
+            let content = render_inline(
+                &element.children,
+                InlineContext {
+                    italic: true,
+                    ..context
+                },
+            );
+            wrap(&content, "*")
+        },
+        _ => {
+            let content = render_inline(&element.children, context);
+            if element.is_block() {
+                format!(" {content} ")
+            } else {
+                content
+            }
+        },
+    }
+}
+
+fn render_link(element: &Element, context: InlineContext) -> String {
+    let content = render_inline(&element.children, InlineContext { link: true, ..context });
+    let url = element.attribute("href").map(decode_entities).unwrap_or_default();
+    let url = url.trim();
+    let text = collapse_lines(&content);
+    if url.is_empty() || url.starts_with('#') || url.to_ascii_lowercase().starts_with("javascript:") || text.is_empty()
+    {
+        return content;
+    }
+
+    let draft = LinkDraft {
+        text: text.clone(),
+        url: url.to_string(),
+        title: element.attribute("title").map(decode_entities).unwrap_or_default(),
+        range: Selection::default(),
+    };
+    let leading = &content[..content.len() - content.trim_start().len()];
+    let trailing = &content[content.trim_end().len()..];
+    format!("{leading}[{text}]({}){trailing}", draft.destination())
+}
+
+fn render_image(element: &Element) -> String {
+    let Some(src) = element
+        .attribute("src")
+        .map(decode_entities)
+        .filter(|src| !src.trim().is_empty())
+    else {
+        return String::new();
+    };
+
+    let alt = element.attribute("alt").map(decode_entities).unwrap_or_default();
+    let draft = LinkDraft {
+        text: alt.clone(),
+        url: src,
+        title: element.attribute("title").map(decode_entities).unwrap_or_default(),
+        range: Selection::default(),
+    };
+    let mut text = String::new();
+    push_text(&mut text, &alt);
+    format!("![{}]({})", collapse_lines(&text), draft.destination())
+}
+
+fn render_code_block(element: &Element) -> String {
+    let mut code = String::new();
+    element.text_content(&mut code);
+    let code = code.strip_prefix('\n').unwrap_or(&code).trim_end();
+
+    let language = [Some(element), element.find(&|element| element.name == "code")]
+        .into_iter()
+        .flatten()
+        .filter_map(|element| element.attribute("class"))
+        .flat_map(str::split_whitespace)
+        .find_map(|class| class.strip_prefix("language-").or_else(|| class.strip_prefix("lang-")))
+        .unwrap_or_default();
+
+    let fence = fence_for(code);
+    format!("{fence}{language}\n{code}\n{fence}")
+}
+
+fn render_list(element: &Element) -> String {
+    let ordered = element.name == "ol";
+    let mut number = element
+        .attribute("start")
+        .and_then(|start| start.trim().parse::<usize>().ok())
+        .unwrap_or(1);
+    let mut items = Vec::new();
+    let mut indent = String::new();
+
+    for child in &element.children {
+        let item = match child {
+            Node::Element(item) if item.name == "li" => item,
+            Node::Element(nested) if nested.name == "ul" || nested.name == "ol" => {
+                if let Some(last) = items.last_mut() {
+                    *last = format!("{last}\n{}", prefix_lines(&render_list(nested), &indent, ""));
+                }
+                continue;
+            },
+            Node::Element(_) => {
+                let content = render_blocks(std::slice::from_ref(child));
+                if !content.is_empty() {
+                    items.push(content);
+                }
+                continue;
+            },
+            Node::Text(_) => continue,
+        };
+
+        let marker = if ordered {
+            let marker = format!("{number}. ");
+            number += 1;
+            marker
+        } else {
+            "- ".to_string()
+        };
+        let task = task_checkbox(item)
+            .map(|checkbox| {
+                if checkbox.attribute("checked").is_some() {
+                    "[x] "
+                } else {
+                    "[ ] "
+                }
+            })
+            .unwrap_or_default();
+
+        let mut writer = Writer::default();
+        writer.write_nodes(&item.children);
+        writer.flush();
+        let mut content = String::new();
+        for block in writer.blocks {
+            if !content.is_empty() {
+                content.push_str(if is_list(&block) { "\n" } else { "\n\n" });
+            }
+            content.push_str(&block);
+        }
+
+        indent = " ".repeat(marker.len());
+        let content = prefix_lines(&content, &indent, "");
+        let item = format!("{marker}{task}{}", &content[indent.len().min(content.len())..]);
+        items.push(item.trim_end().to_string());
+    }
+    items.join("\n")
+}
+
+fn task_checkbox(item: &Element) -> Option<&Element> {
+    item.children.iter().find_map(|child| match child {
+        Node::Element(element) if element.is_checkbox() => Some(element),
+        Node::Element(element) if element.name != "ul" && element.name != "ol" => task_checkbox(element),
+        _ => None,
+    })
+}
+
+fn is_list(block: &str) -> bool {
+    block.starts_with("- ") || {
+        let digits = block.chars().take_while(char::is_ascii_digit).count();
+        digits > 0 && block[digits..].starts_with(". ")
+    }
+}
+
+fn render_table(element: &Element) -> String {
+    let mut rows: Vec<Vec<(String, Option<&'static str>)>> = Vec::new();
+    collect_rows(element, &mut rows);
+    let Some(columns) = rows.iter().map(Vec::len).max().filter(|&columns| columns > 0) else {
+        return String::new();
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    for (index, row) in rows.iter().enumerate() {
+        let cells: Vec<&str> = (0..columns)
+            .map(|column| row.get(column).map(|(cell, _)| cell.as_str()).unwrap_or_default())
+            .collect();
+        lines.push(format!("| {} |", cells.join(" | ")));
+        if index == 0 {
+            let delimiters: Vec<&str> = (0..columns)
+                .map(|column| row.get(column).and_then(|(_, align)| *align).unwrap_or("---"))
+                .collect();
+            lines.push(format!("| {} |", delimiters.join(" | ")));
+        }
+    }
+    lines.join("\n")
+}
+
+fn collect_rows(element: &Element, rows: &mut Vec<Vec<(String, Option<&'static str>)>>) {
+    for child in &element.children {
+        let Node::Element(child) = child else {
+            continue;
+        };
+        match child.name.as_str() {
+            "tr" => rows.push(
+                child
+                    .children
+                    .iter()
+                    .filter_map(|cell| match cell {
+                        Node::Element(cell) if cell.name == "td" || cell.name == "th" => Some(render_cell(cell)),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            "table" => (),
+            _ => collect_rows(child, rows),
+        }
+    }
+}
+
+fn render_cell(cell: &Element) -> (String, Option<&'static str>) {
+    let content = collapse_lines(&render_inline(&cell.children, InlineContext::default())).replace('|', "\\|");
+    let align = cell
+        .attribute("align")
+        .map(str::to_ascii_lowercase)
+        .or_else(|| cell.style("text-align"));
+    let align = match align.as_deref() {
+        Some("left") => Some(":---"),
+        Some("center") => Some(":---:"),
+        Some("right") => Some("---:"),
+        _ => None,
+    };
+    (content, align)
+}
+
+fn wrap(content: &str, delimiter: &str) -> String {
+    let trimmed = content.trim();
+    if trimmed.is_empty() {
+        return content.to_string();
+    }
+    let leading = &content[..content.len() - content.trim_start().len()];
+    let trailing = &content[content.trim_end().len()..];
+    format!("{leading}{delimiter}{trimmed}{delimiter}{trailing}")
+}
+
+fn code_span(code: &str) -> String {
+    let code = code.replace('\n', " ");
+    if code.is_empty() {
+        return String::new();
+    }
+
+    let fence = "`".repeat(longest_run(&code, '`') + 1);
+    let padding = if code.starts_with('`') || code.ends_with('`') || (code.starts_with(' ') && code.ends_with(' ')) {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{padding}{code}{padding}{fence}")
+}
+
+fn longest_run(text: &str, target: char) -> usize {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in text.chars() {
+        if ch == target {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    longest
+}
+
+fn push_text(output: &mut String, text: &str) {
+    let chars: Vec<char> = text.chars().collect();
+    for (index, &ch) in chars.iter().enumerate() {
+        match ch {
+            ch if ch.is_whitespace() => {
+                if !output.ends_with(' ') {
+                    output.push(' ');
+                }
+            },
+            '\\' | '*' | '`' | '[' | ']' | '<' => {
+                output.push('\\');
+                output.push(ch);
+            },
+            '&' if is_entity_like(&chars[index + 1..]) => output.push_str("\\&"),
+            '_' => {
+                let is_intraword = index > 0
+                    && chars[index - 1].is_alphanumeric()
+                    && chars.get(index + 1).is_some_and(|ch| ch.is_alphanumeric());
+                if !is_intraword {
+                    output.push('\\');
+                }
+                output.push(ch);
+            },
+            ch => output.push(ch),
+        }
+    }
+}
+
+fn is_entity_like(rest: &[char]) -> bool {
+    let name_len = rest
+        .iter()
+        .take_while(|ch| ch.is_ascii_alphanumeric() || **ch == '#')
+        .count();
+    name_len > 0 && rest.get(name_len) == Some(&';')
+}
+
+fn push_inline(output: &mut String, rendered: &str) {
+    if output.ends_with(' ') {
+        output.push_str(rendered.trim_start_matches(' '));
+    } else {
+        output.push_str(rendered);
+    }
+}
+
+fn escape_line_start(line: &str) -> String {
+    let digits = line.chars().take_while(char::is_ascii_digit).count();
+    let is_block_marker = line.starts_with(['#', '>'])
+        || ["- ", "+ "]
+            .iter()
+            .any(|marker| line.starts_with(marker) || line == marker.trim())
+        || (!line.is_empty() && (line.chars().all(|ch| ch == '-') || line.chars().all(|ch| ch == '=')))
+        || (digits > 0 && digits < 10 && (line[digits..].starts_with(". ") || line[digits..].starts_with(") ")));
+
+    if !is_block_marker {
+        line.to_string()
+    } else if digits > 0 {
+        format!("{}\\{}", &line[..digits], &line[digits..])
+    } else {
+        format!("\\{line}")
+    }
+}
+
+fn collapse_lines(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn prefix_lines(text: &str, prefix: &str, empty_prefix: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                empty_prefix.to_string()
+            } else {
+                format!("{prefix}{line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_nested_lists() {
+        assert_eq!(
+            html_to_markdown("<ul><li>a<ul><li>b</li></ul></li><li>c</li></ul>"),
+            "- a\n  - b\n- c"
+        );
+        assert_eq!(
+            html_to_markdown("<ol start=\"3\"><li>a</li><li>b<ol><li>c</li></ol></li></ol>"),
+            "3. a\n4. b\n   1. c"
+        );
+    }
+
+    #[test]
+    fn converts_task_lists_and_headings() {
+        assert_eq!(
+            html_to_markdown(
+                "<ul><li><input type=\"checkbox\" checked> done</li><li><input type=checkbox> todo</li></ul>"
+            ),
+            "- [x] done\n- [ ] todo"
+        );
+        assert_eq!(html_to_markdown("<h1>T</h1><h3>S <em>x</em></h3>"), "# T\n\n### S *x*");
+    }
+
+    #[test]
+    fn converts_code_with_backticks() {
+        assert_eq!(
+            html_to_markdown("<pre><code class=\"language-rust\">let a = `b`;\n```\n</code></pre>"),
+            "````rust\nlet a = `b`;\n```\n````"
+        );
+        assert_eq!(html_to_markdown("<p>use <code>a`b</code></p>"), "use ``a`b``");
+    }
+
+    #[test]
+    fn converts_links_and_images() {
+        assert_eq!(
+            html_to_markdown("<a href=\"https://x.y/a b\" title=\"T\">link</a> <img src=\"/i.png\" alt=\"A [1]\">"),
+            "[link](<https://x.y/a b> \"T\") ![A \\[1\\]](/i.png)"
+        );
+    }
+
+    #[test]
+    fn escapes_markdown_characters() {
+        assert_eq!(
+            html_to_markdown("<p>*a* _b_ snake_case [c] `e` &amp;copy; &amp; x</p>"),
+            "\\*a\\* \\_b\\_ snake_case \\[c\\] \\`e\\` \\&copy; & x"
+        );
+        assert_eq!(
+            html_to_markdown("<p># no</p><p>1. no</p><p>- no</p><p>&gt; no</p>"),
+            "\\# no\n\n1\\. no\n\n\\- no\n\n\\> no"
+        );
+    }
+
+    #[test]
+    fn decodes_entities_in_text() {
+        assert_eq!(
+            html_to_markdown("<p>&lt;tag&gt; &copy; &#x41;&#66; &bogus;</p>"),
+            "\\<tag> © AB \\&bogus;"
+        );
+    }
+
+    #[test]
+    fn tolerates_malformed_html() {
+        assert_eq!(html_to_markdown("<p><b>bold <i>both</p>"), "**bold *both***");
+        assert_eq!(html_to_markdown("<div><p>a<p>b</div>"), "a\n\nb");
+        assert_eq!(html_to_markdown("<p>x</p><!-- c --><p>y"), "x\n\ny");
+        assert_eq!(html_to_markdown("<p>a</b></i> <script>alert(1)</script>b"), "a b");
+    }
+
+    #[test]
+    fn detects_rich_html() {
+        assert!(is_rich_html("<p>a <b>b</b></p>"));
+        assert!(!is_rich_html("<p>plain</p>"));
+    }
+}
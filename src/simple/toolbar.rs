@@ -1,13 +1,27 @@
+use std::cell::RefCell;
 use std::ops::{Add, AddAssign, Range, Sub, SubAssign};
+use std::rc::Rc;
 
-use derive_more::{Add, AddAssign, Sub, SubAssign};
 use wasm_bindgen::JsCast;
 use web_sys::{Event, EventTarget, HtmlTextAreaElement};
-use yew::{html, Html};
+use yew::{function_component, html, use_context, Html, NodeRef, Properties};
 
+use self::heading::HeadingStyle;
+use self::link::LinkTool;
+use self::tool::{BuiltinAction, EditorAction, Tool};
+pub use self::unit::SelectionUnit;
+use crate::keymap::Keymap;
 use crate::Widget;
 
+pub mod code;
+pub mod heading;
+pub mod image;
+mod inline;
+pub mod link;
+pub mod list;
+pub mod table;
 pub mod tool;
+pub mod unit;
 
 pub struct SimpleToolbar {
     pub id: String,
@@ -27,21 +41,26 @@ impl Default for SimpleToolbar {
 
 impl SimpleToolbar {
     pub fn new() -> Self {
-        Self {
-            tools: vec![
-                Box::new(tool::Header::new()),
-                Box::new(tool::Bold::new()),
-                Box::new(tool::Italic::new()),
-                Box::new(tool::Quote::new()),
-                Box::new(tool::Code::new()),
-                Box::new(tool::Link::new()),
-                Box::new(tool::ImageLink::new()),
-                Box::new(tool::BulletedList::new()),
-                Box::new(tool::OrderedList::new()),
-                Box::new(tool::TaskList::new()),
-            ],
-            ..Self::default()
-        }
+        Self::default()
+            .add_action(BuiltinAction::Header(HeadingStyle::Atx))
+            .add_action(BuiltinAction::Bold)
+            .add_action(BuiltinAction::Italic)
+            .add_action(BuiltinAction::Quote)
+            .add_action(BuiltinAction::Code)
+            .add_tool(LinkTool::new())
+            .add_action(BuiltinAction::ImageLink)
+            .add_action(BuiltinAction::BulletedList)
+            .add_action(BuiltinAction::OrderedList)
+            .add_action(BuiltinAction::TaskList)
+            .add_action(BuiltinAction::ToggleTask)
+    }
+
+    pub fn with_extended_formatting(self) -> Self {
+        self.add_action(BuiltinAction::Strikethrough)
+            .add_action(BuiltinAction::Highlight)
+            .add_action(BuiltinAction::Superscript)
+            .add_action(BuiltinAction::Subscript)
+            .add_action(BuiltinAction::Underline)
     }
 
     pub fn with_id(mut self, id: impl Into<String>) -> Self {
@@ -58,6 +77,31 @@ impl SimpleToolbar {
         self.tools.push(Box::new(tool));
         self
     }
+
+    pub fn add_action(self, action: impl EditorAction + 'static) -> Self {
+        self.add_tool(Tool::new(action))
+    }
+
+    pub fn keymap(&self) -> Keymap {
+        let mut keymap = Keymap::new();
+        for (shortcut, action) in self.tools.iter().filter_map(|tool| tool.key_binding()) {
+            keymap.insert(shortcut, action);
+        }
+        keymap
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ToolbarKeymapProps {
+    pub keymap: Keymap,
+}
+
+#[function_component(ToolbarKeymap)]
+pub fn toolbar_keymap(props: &ToolbarKeymapProps) -> Html {
+    if let Some(context) = use_context::<EditorContext>() {
+        *context.keymap.borrow_mut() = props.keymap.clone();
+    }
+    html! {}
 }
 
 impl Widget for SimpleToolbar {
@@ -65,6 +109,7 @@ impl Widget for SimpleToolbar {
         let item_class = format!("{}_item", self.class);
         html! {
             <ul id = { self.id.clone() } class = { self.class.clone() }>
+                <ToolbarKeymap keymap = { self.keymap() } />
                 {
                     self.tools
                         .iter()
@@ -82,13 +127,57 @@ impl From<SimpleToolbar> for Html {
     }
 }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Add, Sub, AddAssign, SubAssign)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Selection {
     pub start: usize,
     pub end: usize,
+    pub unit: SelectionUnit,
 }
 
 impl Selection {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self::with_unit(start, end, SelectionUnit::Char)
+    }
+
+    pub fn with_unit(start: usize, end: usize, unit: SelectionUnit) -> Self {
+        Self { start, end, unit }
+    }
+
+    pub fn convert(self, text: &str, unit: SelectionUnit) -> Self {
+        if self.unit == unit {
+            return self;
+        }
+
+        Self {
+            start: self.unit.convert(text, self.start, unit),
+            end: self.unit.convert(text, self.end, unit),
+            unit,
+        }
+    }
+
+    pub fn to_chars(self, text: &str) -> Self {
+        self.convert(text, SelectionUnit::Char)
+    }
+
+    pub fn to_utf16(self, text: &str) -> Self {
+        self.convert(text, SelectionUnit::Utf16)
+    }
+
+    pub fn to_bytes(self, text: &str) -> Self {
+        self.convert(text, SelectionUnit::Byte)
+    }
+
+    pub fn snap_to_graphemes(self, text: &str) -> Self {
+        let selection = self.to_chars(text);
+        let start = unit::grapheme_floor(text, selection.start);
+        let end = if selection.is_empty() {
+            start
+        } else {
+            unit::grapheme_ceil(text, selection.end)
+        };
+        Self::new(start, end).convert(text, self.unit)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -96,14 +185,19 @@ impl Selection {
     pub fn len(&self) -> usize {
         self.end - self.start
     }
+
+    pub fn clamp(self, max: usize) -> Self {
+        Self {
+            start: self.start.min(max),
+            end: self.end.min(max),
+            unit: self.unit,
+        }
+    }
 }
 
 impl From<Range<usize>> for Selection {
     fn from(range: Range<usize>) -> Self {
-        Self {
-            start: range.start,
-            end: range.end,
-        }
+        Self::new(range.start, range.end)
     }
 }
 
@@ -139,46 +233,83 @@ impl SubAssign<usize> for Selection {
     }
 }
 
-pub fn textarea_selection(textarea_selector: impl AsRef<str>) -> Option<(HtmlTextAreaElement, String, Selection)> {
-    let element = wasm_dom::existing::document()
-        .query_selector(textarea_selector.as_ref())
-        .ok()??;
-    let textarea = element.dyn_into::<HtmlTextAreaElement>().ok()?;
-    let text = textarea.value();
-    let start_char = textarea
-        .selection_start()
-        .map(|start| start.unwrap_or(0) as usize)
-        .unwrap_or(0);
-    let mut end_char = textarea
-        .selection_end()
-        .map(|end| end.unwrap_or(0) as usize)
-        .unwrap_or(0);
-    if end_char < start_char {
-        end_char = start_char;
-    }
-
-    Some((textarea, text, Selection {
-        start: start_char,
-        end: end_char,
-    }))
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct EditorState {
+    pub text: String,
+    pub selection: Selection,
 }
 
-pub fn replace_selected_in_textarea(
-    selection: Option<(HtmlTextAreaElement, String, Selection)>,
-    fmt: impl Into<ReplaceFmt>,
-    mode: UnselectedApplyMode,
-) {
-    if let Some((textarea, text, selection)) = selection {
-        let (text, selection) = fmt.into().layout(text, selection, mode);
+impl EditorState {
+    pub fn new(text: impl Into<String>, selection: impl Into<Selection>) -> Self {
+        let text = text.into();
+        let selection = selection.into().to_chars(&text);
+        Self { text, selection }
+    }
 
-        textarea.set_value(&text);
-        textarea.focus().ok();
+    pub fn from_textarea(textarea: &HtmlTextAreaElement) -> Self {
+        let start = textarea
+            .selection_start()
+            .map(|start| start.unwrap_or(0) as usize)
+            .unwrap_or(0);
+        let mut end = textarea
+            .selection_end()
+            .map(|end| end.unwrap_or(0) as usize)
+            .unwrap_or(0);
+        if end < start {
+            end = start;
+        }
+
+        Self::new(textarea.value(), Selection::with_unit(start, end, SelectionUnit::Utf16))
+    }
+
+    pub fn insert_text(&mut self, text: &str) {
+        let mut chars: Vec<char> = self.text.chars().collect();
+        let selection = self
+            .selection
+            .snap_to_graphemes(&self.text)
+            .to_chars(&self.text)
+            .clamp(chars.len());
+        let end = selection.start + text.chars().count();
+        chars.splice(selection.start..selection.end, text.chars());
+
+        self.text = chars.into_iter().collect();
+        self.selection = Selection::new(end, end);
+    }
+
+    pub fn char_selection(&self) -> Selection {
+        self.selection.to_chars(&self.text)
+    }
+
+    pub fn selected_text(&self) -> String {
+        let selection = self.char_selection();
+        self.text.chars().skip(selection.start).take(selection.len()).collect()
+    }
+
+    pub fn replace(&mut self, fmt: impl Into<ReplaceFmt>, mode: UnselectedApplyMode) {
+        let text = std::mem::take(&mut self.text);
+        let (text, selection) = fmt.into().layout(text, self.selection, mode);
+        self.text = text;
+        self.selection = selection;
+    }
+
+    pub fn write_to_textarea(&self, textarea: &HtmlTextAreaElement) {
+        let selection = self.selection.to_utf16(&self.text);
+        textarea.set_value(&self.text);
         textarea
             .set_selection_start(Some(selection.start as u32))
             .expect("Set selection start failed");
         textarea
             .set_selection_end(Some(selection.end as u32))
             .expect("Set selection end failed");
+    }
+
+    pub fn apply_to_textarea(&self, textarea: &HtmlTextAreaElement) {
+        textarea.focus().ok();
+        self.update_textarea(textarea);
+    }
+
+    pub fn update_textarea(&self, textarea: &HtmlTextAreaElement) {
+        self.write_to_textarea(textarea);
         let target: &EventTarget = textarea.as_ref();
         target
             .dispatch_event(&Event::new("input").expect("Input event expected"))
@@ -186,9 +317,79 @@ pub fn replace_selected_in_textarea(
     }
 }
 
+pub fn find_textarea(textarea_selector: impl AsRef<str>) -> Option<HtmlTextAreaElement> {
+    let element = wasm_dom::existing::document()
+        .query_selector(textarea_selector.as_ref())
+        .ok()??;
+    element.dyn_into::<HtmlTextAreaElement>().ok()
+}
+
+pub fn textarea_selection(textarea_selector: impl AsRef<str>) -> Option<(HtmlTextAreaElement, String, Selection)> {
+    let textarea = find_textarea(textarea_selector)?;
+    let EditorState { text, selection } = EditorState::from_textarea(&textarea);
+
+    Some((textarea, text, selection))
+}
+
+pub fn replace_selected_in_textarea(
+    selection: Option<(HtmlTextAreaElement, String, Selection)>,
+    fmt: impl Into<ReplaceFmt>,
+    mode: UnselectedApplyMode,
+) {
+    if let Some((textarea, text, selection)) = selection {
+        let mut state = EditorState::new(text, selection);
+        state.replace(fmt, mode);
+        state.apply_to_textarea(&textarea);
+    }
+}
+
+pub fn edit_in_textarea(textarea_selector: impl AsRef<str>, edit: impl FnOnce(&mut EditorState)) {
+    if let Some(textarea) = find_textarea(textarea_selector) {
+        edit_textarea(&textarea, edit);
+    }
+}
+
+pub fn edit_textarea(textarea: &HtmlTextAreaElement, edit: impl FnOnce(&mut EditorState)) {
+    let mut state = EditorState::from_textarea(textarea);
+    edit(&mut state);
+    state.apply_to_textarea(textarea);
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EditorContext {
+    pub textarea: NodeRef,
+    pub keymap: Rc<RefCell<Keymap>>,
+}
+
+impl EditorContext {
+    pub fn new(textarea: NodeRef) -> Self {
+        Self {
+            textarea,
+            keymap: Default::default(),
+        }
+    }
+
+    pub fn with_keymap(mut self, keymap: Rc<RefCell<Keymap>>) -> Self {
+        self.keymap = keymap;
+        self
+    }
+
+    pub fn textarea(&self) -> Option<HtmlTextAreaElement> {
+        self.textarea.cast()
+    }
+}
+
+impl PartialEq for EditorContext {
+    fn eq(&self, other: &Self) -> bool {
+        self.textarea == other.textarea && Rc::ptr_eq(&self.keymap, &other.keymap)
+    }
+}
+
 pub enum ReplaceFmt {
     Around(String, String),
     StartLine(String),
+    Indent(String),
+    Outdent(String),
 }
 
 impl ReplaceFmt {
@@ -198,21 +399,27 @@ impl ReplaceFmt {
         selection: impl Into<Selection>,
         mode: UnselectedApplyMode,
     ) -> (String, Selection) {
+        let selection = selection.into().to_chars(&text).snap_to_graphemes(&text);
+        match self {
+            ReplaceFmt::Indent(unit) => return Self::indent_layout(text, unit, selection, list::indent_lines),
+            ReplaceFmt::Outdent(unit) => return Self::indent_layout(text, unit, selection, list::outdent_lines),
+            _ => (),
+        }
+
         let text: Vec<_> = text.chars().collect();
-        let selection = selection.into();
         let replacement = if selection.is_empty() {
-            Selection {
-                start: text[0..selection.start]
+            Selection::new(
+                text[0..selection.start]
                     .iter()
                     .rposition(|&ch| mode.is_start_boundary(ch))
                     .map(|pos| pos + 1)
                     .unwrap_or(0),
-                end: text[selection.end..]
+                text[selection.end..]
                     .iter()
                     .position(|&ch| mode.is_end_boundary(ch))
                     .map(|pos| selection.start + pos)
                     .unwrap_or_else(|| text.len()),
-            }
+            )
         } else {
             selection
         };
@@ -220,9 +427,21 @@ impl ReplaceFmt {
         match self {
             ReplaceFmt::Around(prefix, suffix) => Self::around_layout(text, prefix, suffix, selection, replacement),
             ReplaceFmt::StartLine(prefix) => Self::start_line_layout(text, prefix, selection, replacement),
+            ReplaceFmt::Indent(_) | ReplaceFmt::Outdent(_) => unreachable!("Indentation is laid out by lines"),
         }
     }
 
+    fn indent_layout(
+        text: String,
+        unit: &str,
+        selection: Selection,
+        indent: fn(&mut EditorState, &str) -> bool,
+    ) -> (String, Selection) {
+        let mut state = EditorState::new(text, selection);
+        indent(&mut state, unit);
+        (state.text, state.selection)
+    }
+
     fn around_layout(
         text: Vec<char>,
         prefix: &str,
@@ -230,6 +449,13 @@ impl ReplaceFmt {
         selection: Selection,
         replacement: Selection,
     ) -> (String, Selection) {
+        if inline::is_code_span_delimiter(prefix, suffix) {
+            return Self::code_span_layout(text, selection, replacement);
+        }
+        if let Some(delimiter) = inline::emphasis_delimiter(prefix, suffix) {
+            return Self::emphasis_layout(text, delimiter, prefix.chars().count(), selection, replacement);
+        }
+
         let before: String = text[..replacement.start].iter().collect();
         let source: String = text[replacement.start..replacement.end].iter().collect();
         let after: String = text[replacement.end..].iter().collect();
@@ -238,21 +464,19 @@ impl ReplaceFmt {
         let result_text;
         let result_selection;
 
-        if before.ends_with(prefix) && after.starts_with(suffix) {
-            result_text = before.trim_end_matches(prefix).to_string() + &source + after.trim_start_matches(suffix);
+        if let (Some(before), Some(after)) = (before.strip_suffix(prefix), after.strip_prefix(suffix)) {
+            result_text = before.to_string() + &source + after;
             result_selection = selection - prefix_chars;
         } else if source.starts_with(prefix) && source.ends_with(suffix) && source.len() >= prefix.len() + suffix.len()
         {
             let before_chars = before.chars().count();
-            result_text = before + source.trim_start_matches(prefix).trim_end_matches(suffix) + &after;
+            let source = &source[prefix.len()..source.len() - suffix.len()];
+            result_text = before + source + &after;
             result_selection = if selection.is_empty() {
-                let pos = before_chars.max(selection.start - prefix_chars);
-                Selection { start: pos, end: pos }
+                let pos = before_chars.max(selection.start.saturating_sub(prefix_chars));
+                Selection::new(pos, pos)
             } else {
-                Selection {
-                    start: selection.start,
-                    end: selection.end - prefix_chars - suffix.chars().count(),
-                }
+                Selection::new(selection.start, selection.end - prefix_chars - suffix.chars().count())
             };
         } else {
             result_text = before + prefix + &source + suffix + &after;
@@ -262,6 +486,91 @@ impl ReplaceFmt {
         (result_text, result_selection)
     }
 
+    fn code_span_layout(mut text: Vec<char>, mut selection: Selection, replacement: Selection) -> (String, Selection) {
+        let span = inline::code_spans(&text)
+            .into_iter()
+            .find(|span| span.start <= replacement.start && replacement.end <= span.end);
+
+        let is_empty_span = text[replacement.start..replacement.end] == ['`', '`']
+            && selection == Selection::from(replacement.start + 1..replacement.start + 1);
+        if is_empty_span {
+            remove_chars(&mut text, &mut selection, replacement.start..replacement.end);
+        } else if let Some(span) = span {
+            let content = inline::code_span_content(&text, span.clone());
+            remove_chars(&mut text, &mut selection, content.end..span.end);
+            remove_chars(&mut text, &mut selection, span.start..content.start);
+        } else {
+            let (fence, padding) = inline::code_span_fence(&text[replacement.start..replacement.end]);
+            let prefix: Vec<char> = fence.chars().chain(padding.chars()).collect();
+            let suffix: Vec<char> = padding.chars().chain(fence.chars()).collect();
+            insert_chars(&mut text, &mut selection, replacement.end, &suffix, false);
+            insert_chars(&mut text, &mut selection, replacement.start, &prefix, true);
+        }
+
+        (text.into_iter().collect(), selection)
+    }
+
+    fn emphasis_layout(
+        mut text: Vec<char>,
+        delimiter: char,
+        layer_len: usize,
+        mut selection: Selection,
+        replacement: Selection,
+    ) -> (String, Selection) {
+        let range = inline::enclosing_inline(&text, replacement.start..replacement.end);
+        let delimiters = match delimiter {
+            '*' => ['*', '_'],
+            '_' => ['_', '*'],
+            _ => [delimiter, delimiter],
+        };
+        let layer = delimiters
+            .iter()
+            .find_map(|&delimiter| Self::emphasis_layer(&text, delimiter, layer_len, range.clone()));
+
+        if let Some((open, close)) = layer {
+            remove_chars(&mut text, &mut selection, close);
+            remove_chars(&mut text, &mut selection, open);
+        } else {
+            let layer: Vec<char> = std::iter::repeat_n(delimiter, layer_len).collect();
+            insert_chars(&mut text, &mut selection, range.end, &layer, false);
+            insert_chars(&mut text, &mut selection, range.start, &layer, true);
+        }
+
+        (text.into_iter().collect(), selection)
+    }
+
+    fn emphasis_layer(
+        text: &[char],
+        delimiter: char,
+        layer_len: usize,
+        range: Range<usize>,
+    ) -> Option<(Range<usize>, Range<usize>)> {
+        let run_before = inline::run_len_before(text, range.start, delimiter);
+        let run_after = inline::run_len_after(text, range.end, delimiter);
+        let outer_run = run_before.min(run_after);
+
+        let source = &text[range.clone()];
+        let inner_before = inline::run_len_after(source, 0, delimiter);
+        let inner_after = inline::run_len_before(source, source.len(), delimiter);
+        let inner_run = if inner_before + inner_after < source.len() {
+            inner_before.min(inner_after)
+        } else {
+            0
+        };
+
+        if outer_run > 0 && inline::has_emphasis_layer(delimiter, outer_run, layer_len) {
+            Some((range.start - layer_len..range.start, range.end..range.end + layer_len))
+        } else if outer_run == 0 && inner_run > 0 && inline::has_emphasis_layer(delimiter, inner_run, layer_len) {
+            let content = range.start + inner_before..range.end - inner_after;
+            Some((
+                content.start - layer_len..content.start,
+                content.end..content.end + layer_len,
+            ))
+        } else {
+            None
+        }
+    }
+
     fn start_line_layout(
         text: Vec<char>,
         prefix: &str,
@@ -291,10 +600,10 @@ impl ReplaceFmt {
             }
 
             result_text = before.trim_end_matches(prefix).to_string() + &target + &after;
-            result_selection = Selection {
-                start: selection.start - prefix_chars,
-                end: selection.end - prefix_chars * lines.len(),
-            }
+            result_selection = Selection::new(
+                selection.start - prefix_chars,
+                selection.end - prefix_chars * lines.len(),
+            )
         } else if is_prefixed_lines && (before.is_empty() || before.ends_with('\n')) && source.starts_with(prefix) {
             let mut target = String::new();
             for (idx, line) in lines.iter().enumerate() {
@@ -308,10 +617,7 @@ impl ReplaceFmt {
             result_selection = if selection.is_empty() {
                 selection - prefix_chars.min(selection.start - replacement.start)
             } else {
-                Selection {
-                    start: selection.start,
-                    end: selection.end - prefix_chars * lines.len(),
-                }
+                Selection::new(selection.start, selection.end - prefix_chars * lines.len())
             };
         } else if is_prefixed_lines && source.starts_with(&block_prefix) {
             let mut target = String::new();
@@ -325,10 +631,10 @@ impl ReplaceFmt {
             }
 
             result_text = before + &target + &after;
-            result_selection = Selection {
-                start: selection.start - prefix_chars,
-                end: selection.end - prefix_chars * lines.len(),
-            };
+            result_selection = Selection::new(
+                selection.start - prefix_chars,
+                selection.end - prefix_chars * lines.len(),
+            );
         } else {
             let before_endline = before.is_empty() || before.ends_with('\n');
             let mut target = String::new();
@@ -347,12 +653,12 @@ impl ReplaceFmt {
             result_selection = if selection.is_empty() {
                 selection + prefix_chars
             } else {
-                Selection {
-                    start: selection.start
+                Selection::new(
+                    selection.start
                         + if before_endline { 0 } else { 1 }
                         + if lines.len() < 2 { prefix_chars } else { 0 },
-                    end: selection.end + if before_endline { 0 } else { 1 } + prefix_chars * lines.len(),
-                }
+                    selection.end + if before_endline { 0 } else { 1 } + prefix_chars * lines.len(),
+                )
             };
         }
 
@@ -360,14 +666,64 @@ impl ReplaceFmt {
     }
 }
 
+fn remove_chars(text: &mut Vec<char>, selection: &mut Selection, range: Range<usize>) {
+    let shift = |pos: usize| {
+        if pos <= range.start {
+            pos
+        } else if pos >= range.end {
+            pos - range.len()
+        } else {
+            range.start
+        }
+    };
+    selection.start = shift(selection.start);
+    selection.end = shift(selection.end);
+    text.drain(range);
+}
+
+fn insert_chars(text: &mut Vec<char>, selection: &mut Selection, pos: usize, chars: &[char], is_prefix: bool) {
+    let shift = |sel_pos: usize| {
+        if sel_pos > pos || (is_prefix && sel_pos == pos) {
+            sel_pos + chars.len()
+        } else {
+            sel_pos
+        }
+    };
+    selection.start = shift(selection.start);
+    selection.end = shift(selection.end);
+    text.splice(pos..pos, chars.iter().copied());
+}
+
 impl<Before: Into<String>, After: Into<String>> From<(Before, After)> for ReplaceFmt {
     fn from((before, after): (Before, After)) -> Self {
         Self::Around(before.into(), after.into())
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Spaces(usize),
+    Tab,
+}
+
+impl IndentStyle {
+    pub fn unit(&self) -> String {
+        match self {
+            IndentStyle::Spaces(width) => " ".repeat(*width),
+            IndentStyle::Tab => "\t".to_string(),
+        }
+    }
+}
+
+impl Default for IndentStyle {
+    fn default() -> Self {
+        Self::Spaces(4)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
 pub enum UnselectedApplyMode {
+    #[default]
     Word,
     Line,
     FromWordToEndLine,
@@ -389,8 +745,147 @@ impl UnselectedApplyMode {
     }
 }
 
-impl Default for UnselectedApplyMode {
-    fn default() -> Self {
-        Self::Word
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::toolbar::tool::EditorAction;
+
+    fn layout(fmt: impl Into<ReplaceFmt>, text: &str, selection: Range<usize>) -> (String, Range<usize>) {
+        let (text, selection) = fmt
+            .into()
+            .layout(text.to_string(), selection, UnselectedApplyMode::Word);
+        (text, selection.start..selection.end)
+    }
+
+    fn start_line(prefix: &str) -> ReplaceFmt {
+        ReplaceFmt::StartLine(prefix.to_string())
+    }
+
+    #[test]
+    fn editor_state_converts_selection_to_chars() {
+        let selection = Selection::with_unit(3, 4, SelectionUnit::Utf16);
+        let state = EditorState::new("a😀bc", selection);
+        assert_eq!(state.selection, Selection::new(2, 3));
+        assert_eq!(state.selected_text(), "b");
+    }
+
+    #[test]
+    fn selected_text_converts_a_foreign_unit() {
+        let mut state = EditorState::new("é😀x", 0..0);
+        state.selection = Selection::with_unit(2, 6, SelectionUnit::Byte);
+        assert_eq!(state.char_selection(), Selection::new(1, 2));
+        assert_eq!(state.selected_text(), "😀");
+    }
+
+    #[test]
+    fn snap_to_graphemes_widens_to_whole_clusters() {
+        let text = "a👩\u{200d}👩\u{200d}👧b";
+        assert_eq!(Selection::new(2, 4).snap_to_graphemes(text), Selection::new(1, 6));
+        assert_eq!(Selection::new(3, 3).snap_to_graphemes(text), Selection::new(1, 1));
+
+        let utf16 = Selection::with_unit(2, 2, SelectionUnit::Utf16);
+        assert_eq!(
+            utf16.snap_to_graphemes("a👍🏽"),
+            Selection::with_unit(1, 1, SelectionUnit::Utf16)
+        );
+        assert_eq!(Selection::new(1, 1).snap_to_graphemes("e\u{301}"), Selection::new(0, 0));
+    }
+
+    #[test]
+    fn around_wraps_and_unwraps_selection() {
+        assert_eq!(layout(("**", "**"), "a bc d", 2..4), ("a **bc** d".to_string(), 4..6));
+        assert_eq!(layout(("**", "**"), "a **bc** d", 4..6), ("a bc d".to_string(), 2..4));
+        assert_eq!(
+            layout(("<ins>", "</ins>"), "ab", 0..2),
+            ("<ins>ab</ins>".to_string(), 5..7)
+        );
+        assert_eq!(
+            layout(("<ins>", "</ins>"), "<ins>ab</ins>", 5..7),
+            ("ab".to_string(), 0..2)
+        );
+    }
+
+    #[test]
+    fn around_wraps_word_at_caret() {
+        assert_eq!(layout(("*", "*"), "one two", 5..5), ("one *two*".to_string(), 6..6));
+        assert_eq!(layout(("*", "*"), "one *two*", 6..6), ("one two".to_string(), 5..5));
+    }
+
+    #[test]
+    fn around_toggles_nested_emphasis() {
+        assert_eq!(layout(("*", "*"), "***a***", 3..4), ("**a**".to_string(), 2..3));
+        assert_eq!(layout(("**", "**"), "*a*", 1..2), ("***a***".to_string(), 3..4));
+    }
+
+    #[test]
+    fn around_unwraps_underscore_emphasis() {
+        assert_eq!(layout(("*", "*"), "_a_", 1..2), ("a".to_string(), 0..1));
+        assert_eq!(layout(("**", "**"), "__a__", 2..3), ("a".to_string(), 0..1));
+        assert_eq!(layout(("*", "*"), "_a_", 0..3), ("a".to_string(), 0..1));
+        assert_eq!(layout(("*", "*"), "one _two_", 6..6), ("one two".to_string(), 5..5));
+        assert_eq!(layout(("*", "*"), "___a___", 3..4), ("__a__".to_string(), 2..3));
+        assert_eq!(layout(("**", "**"), "_a_", 1..2), ("_**a**_".to_string(), 3..4));
+    }
+
+    #[test]
+    fn around_wraps_code_spans_with_longer_fences() {
+        assert_eq!(layout(("`", "`"), "a b", 2..3), ("a `b`".to_string(), 3..4));
+        assert_eq!(layout(("`", "`"), "a`b", 0..3), ("``a`b``".to_string(), 2..5));
+        assert_eq!(layout(("`", "`"), "`a", 0..2), ("`` `a ``".to_string(), 3..5));
+        assert_eq!(layout(("`", "`"), "", 0..0), ("``".to_string(), 1..1));
+    }
+
+    #[test]
+    fn around_unwraps_code_spans() {
+        assert_eq!(layout(("`", "`"), "a `b`", 3..4), ("a b".to_string(), 2..3));
+        assert_eq!(layout(("`", "`"), "``a`b``", 2..5), ("a`b".to_string(), 0..3));
+        assert_eq!(layout(("`", "`"), "`` `a ``", 3..5), ("`a".to_string(), 0..2));
+        assert_eq!(layout(("`", "`"), "a `` b", 3..3), ("a  b".to_string(), 2..2));
+    }
+
+    #[test]
+    fn start_line_prefixes_and_unprefixes_lines() {
+        assert_eq!(layout(start_line("> "), "a\nb", 0..3), ("> a\n> b".to_string(), 0..7));
+        assert_eq!(layout(start_line("> "), "> a\n> b", 2..7), ("a\nb".to_string(), 0..3));
+        assert_eq!(layout(start_line("> "), "text", 2..2), ("> text".to_string(), 4..4));
+    }
+
+    #[test]
+    fn indent_and_outdent_lines() {
+        assert_eq!(
+            layout(ReplaceFmt::Indent("  ".to_string()), "a\nb", 0..3),
+            ("  a\n  b".to_string(), 2..7)
+        );
+        assert_eq!(
+            layout(ReplaceFmt::Outdent("  ".to_string()), "  a\n  b", 0..7),
+            ("a\nb".to_string(), 0..3)
+        );
+        assert_eq!(
+            layout(ReplaceFmt::Indent("\t".to_string()), "- a\n- b", 6..6),
+            ("- a\n  - b".to_string(), 8..8)
+        );
+    }
+
+    #[test]
+    fn builtin_actions_edit_state_without_a_textarea() {
+        let applied = |action: BuiltinAction, text: &str, selection: Range<usize>| {
+            let mut state = EditorState::new(text, selection);
+            action.apply(&mut state);
+            state.text
+        };
+
+        assert_eq!(applied(BuiltinAction::Bold, "a b", 2..3), "a **b**");
+        assert_eq!(applied(BuiltinAction::Code, "a b", 2..3), "a `b`");
+        assert_eq!(applied(BuiltinAction::Quote, "a", 0..1), "> a");
+        assert_eq!(applied(BuiltinAction::BulletedList, "a\nb", 0..3), "- a\n- b");
+        assert_eq!(applied(BuiltinAction::Link, "a", 0..1), "[a]()");
+    }
+
+    #[test]
+    fn editor_state_inserts_and_reads_selection() {
+        let mut state = EditorState::new("hello world", 6..11);
+        assert_eq!(state.selected_text(), "world");
+        state.insert_text("there");
+        assert_eq!(state, EditorState::new("hello there", 11..11));
     }
 }
@@ -0,0 +1,483 @@
+use web_sys::{HtmlSelectElement, HtmlTextAreaElement};
+use yew::{
+    html, Callback, Component, Context, Event, Html, KeyboardEvent, MouseEvent, Properties, SubmitEvent, TargetCast,
+};
+
+use super::list::{line_bounds, selected_lines};
+use super::tool::{BuiltinAction, EditorAction};
+use super::{edit_textarea, find_textarea, insert_chars, remove_chars, EditorContext, EditorState, Selection};
+use crate::Widget;
+
+pub const DEFAULT_CODE_LANGUAGES: &[&str] = &[
+    "bash",
+    "c",
+    "cpp",
+    "css",
+    "diff",
+    "go",
+    "html",
+    "java",
+    "javascript",
+    "json",
+    "python",
+    "rust",
+    "sql",
+    "toml",
+    "typescript",
+    "yaml",
+];
+
+const MIN_FENCE_LEN: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub fence: char,
+    pub fence_len: usize,
+    pub info: String,
+    pub opening: Selection,
+    pub content: Selection,
+    pub closing: Option<Selection>,
+}
+
+impl CodeBlock {
+    pub fn end(&self) -> usize {
+        self.closing.map(|closing| closing.end).unwrap_or(self.content.end)
+    }
+}
+
+fn parse_fence(line: &[char]) -> Option<(usize, char, usize, String)> {
+    let indent = line.iter().take_while(|&&ch| ch == ' ').count();
+    let fence = *line.get(indent).filter(|&&ch| ch == '`' || ch == '~')?;
+    let fence_len = line[indent..].iter().take_while(|&&ch| ch == fence).count();
+    if indent > 3 || fence_len < MIN_FENCE_LEN {
+        return None;
+    }
+
+    let info: String = line[indent + fence_len..].iter().collect();
+    if fence == '`' && info.contains('`') {
+        return None;
+    }
+    Some((indent, fence, fence_len, info.trim().to_string()))
+}
+
+pub fn code_blocks(text: &[char]) -> Vec<CodeBlock> {
+    let mut blocks = Vec::new();
+    let mut open: Option<(char, usize, String, Selection)> = None;
+    let mut pos = 0;
+
+    while pos <= text.len() {
+        let line = line_bounds(text, pos);
+        let fence = parse_fence(&text[line.start..line.end]);
+        match (&open, fence) {
+            (None, Some((_, fence, fence_len, info))) => open = Some((fence, fence_len, info, line)),
+            (Some((open_fence, open_len, ..)), Some((_, fence, fence_len, info)))
+                if fence == *open_fence && fence_len >= *open_len && info.is_empty() =>
+            {
+                let (fence, fence_len, info, opening) = open.take().expect("Open fence expected");
+                let content_start = (opening.end + 1).min(line.start);
+                blocks.push(CodeBlock {
+                    fence,
+                    fence_len,
+                    info,
+                    opening,
+                    content: Selection::from(content_start..line.start.saturating_sub(1).max(content_start)),
+                    closing: Some(line),
+                });
+            },
+            _ => (),
+        }
+        pos = line.end + 1;
+    }
+
+    if let Some((fence, fence_len, info, opening)) = open {
+        blocks.push(CodeBlock {
+            fence,
+            fence_len,
+            info,
+            opening,
+            content: Selection::from((opening.end + 1).min(text.len())..text.len()),
+            closing: None,
+        });
+    }
+    blocks
+}
+
+pub fn current_code_block(state: &EditorState) -> Option<CodeBlock> {
+    let text: Vec<char> = state.text.chars().collect();
+    let selection = state.char_selection().clamp(text.len());
+    code_blocks(&text)
+        .into_iter()
+        .find(|block| block.opening.start <= selection.start && selection.end <= block.end())
+}
+
+pub fn fence_for(content: &str) -> String {
+    let mut longest = 0;
+    let mut current = 0;
+    for ch in content.chars() {
+        if ch == '`' {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    "`".repeat((longest + 1).max(MIN_FENCE_LEN))
+}
+
+pub fn toggle_code_block(state: &mut EditorState, info: &str) -> bool {
+    match current_code_block(state) {
+        Some(block) => remove_code_block(state, &block),
+        None => insert_code_block(state, info),
+    }
+    true
+}
+
+fn remove_code_block(state: &mut EditorState, block: &CodeBlock) {
+    let mut text: Vec<char> = state.text.chars().collect();
+    let mut selection = state.char_selection().clamp(text.len());
+
+    if let Some(closing) = block.closing {
+        let start = if closing.start > block.content.start {
+            closing.start - 1
+        } else {
+            closing.start
+        };
+        let end = if start == closing.start {
+            (closing.end + 1).min(text.len())
+        } else {
+            closing.end
+        };
+        remove_chars(&mut text, &mut selection, start..end);
+    }
+    let opening_end = (block.opening.end + 1).min(text.len());
+    remove_chars(&mut text, &mut selection, block.opening.start..opening_end);
+
+    state.text = text.into_iter().collect();
+    state.selection = selection;
+}
+
+fn insert_code_block(state: &mut EditorState, info: &str) {
+    let mut text: Vec<char> = state.text.chars().collect();
+    let mut selection = state.char_selection().clamp(text.len());
+    let lines = selected_lines(&text, selection);
+    let range = Selection::from(lines[0].start..lines[lines.len() - 1].end);
+    let content: String = text[range.start..range.end].iter().collect();
+    let fence = fence_for(&content);
+
+    let opening: Vec<char> = format!("{fence}{}\n", info.trim()).chars().collect();
+    let closing: Vec<char> = format!("\n{fence}").chars().collect();
+    insert_chars(&mut text, &mut selection, range.end, &closing, false);
+    insert_chars(&mut text, &mut selection, range.start, &opening, true);
+
+    state.text = text.into_iter().collect();
+    state.selection = selection;
+}
+
+pub fn set_code_language(state: &mut EditorState, info: &str) -> bool {
+    let Some(block) = current_code_block(state) else {
+        return false;
+    };
+    let info = info.trim();
+    if block.info == info || (block.fence == '`' && info.contains('`')) {
+        return false;
+    }
+
+    let mut text: Vec<char> = state.text.chars().collect();
+    let mut selection = state.char_selection();
+    let line = line_bounds(&text, block.opening.start);
+    let indent = text[line.start..line.end].iter().take_while(|&&ch| ch == ' ').count();
+    let info_start = line.start + indent + block.fence_len;
+    remove_chars(&mut text, &mut selection, info_start..line.end);
+    let info: Vec<char> = info.chars().collect();
+    insert_chars(&mut text, &mut selection, info_start, &info, false);
+
+    state.text = text.into_iter().collect();
+    state.selection = selection;
+    true
+}
+
+#[derive(Debug, Clone)]
+pub struct CodeBlockTool {
+    pub textarea_selector: String,
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub languages: Vec<String>,
+}
+
+impl Default for CodeBlockTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CodeBlockTool {
+    pub fn new() -> Self {
+        Self {
+            textarea_selector: ".lew-simple__textarea".to_string(),
+            class: "lew-simple__tool_button".to_string(),
+            title: BuiltinAction::CodeBlock.title().to_string(),
+            size: 16,
+            languages: DEFAULT_CODE_LANGUAGES
+                .iter()
+                .map(|language| language.to_string())
+                .collect(),
+        }
+    }
+
+    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
+        self.textarea_selector = selector.into();
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_languages(mut self, languages: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.languages = languages.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl Widget for CodeBlockTool {
+    fn build(&self) -> Html {
+        html! {
+            <CodeBlockPopover class = { self.class.clone() } title = { self.title.clone() } size = { self.size }
+                    textarea_selector = { self.textarea_selector.clone() } languages = { self.languages.clone() } />
+        }
+    }
+}
+
+pub enum CodeBlockPopoverMsg {
+    Open,
+    Close,
+    SetLanguage(String),
+    Submit,
+    Remove,
+}
+
+pub struct CodeBlockPopover {
+    is_open: bool,
+    in_block: bool,
+    language: String,
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct CodeBlockPopoverProps {
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub textarea_selector: String,
+
+    #[prop_or(DEFAULT_CODE_LANGUAGES.iter().map(|language| language.to_string()).collect())]
+    pub languages: Vec<String>,
+}
+
+impl CodeBlockPopover {
+    fn textarea(ctx: &Context<Self>) -> Option<HtmlTextAreaElement> {
+        ctx.link()
+            .context::<EditorContext>(Callback::noop())
+            .and_then(|(context, _)| context.textarea())
+            .or_else(|| find_textarea(&ctx.props().textarea_selector))
+    }
+
+    fn view_popover(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|event: SubmitEvent| {
+            event.prevent_default();
+            CodeBlockPopoverMsg::Submit
+        });
+        let onkeydown = ctx.link().batch_callback(|event: KeyboardEvent| {
+            (event.key() == "Escape").then(|| {
+                event.prevent_default();
+                CodeBlockPopoverMsg::Close
+            })
+        });
+        let onchange = ctx.link().callback(|event: Event| {
+            CodeBlockPopoverMsg::SetLanguage(event.target_unchecked_into::<HtmlSelectElement>().value())
+        });
+
+        html! {
+            <form class = "lew-simple__popover" { onsubmit } { onkeydown }>
+                <label class = "lew-simple__popover_field">
+                    { "Language" }
+                    <select { onchange }>
+                        <option value = "" selected = { self.language.is_empty() }>{ "Plain text" }</option>
+                        {
+                            ctx.props()
+                                .languages
+                                .iter()
+                                .map(|language| html! {
+                                    <option value = { language.clone() } selected = { *language == self.language }>
+                                        { language }
+                                    </option>
+                                })
+                                .collect::<Html>()
+                        }
+                    </select>
+                </label>
+                <div class = "lew-simple__popover_actions">
+                    if self.in_block {
+                        <button type = "submit">{ "Apply" }</button>
+                        <button type = "button" onclick = { ctx.link().callback(|_: MouseEvent| CodeBlockPopoverMsg::Remove) }>
+                            { "Remove fence" }
+                        </button>
+                    } else {
+                        <button type = "submit">{ "Insert" }</button>
+                    }
+                    <button type = "button" onclick = { ctx.link().callback(|_: MouseEvent| CodeBlockPopoverMsg::Close) }>
+                        { "Cancel" }
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for CodeBlockPopover {
+    type Message = CodeBlockPopoverMsg;
+    type Properties = CodeBlockPopoverProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            is_open: false,
+            in_block: false,
+            language: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            CodeBlockPopoverMsg::Open => {
+                let block =
+                    Self::textarea(ctx).and_then(|textarea| current_code_block(&EditorState::from_textarea(&textarea)));
+                self.is_open = true;
+                self.in_block = block.is_some();
+                if let Some(block) = block {
+                    self.language = block.info;
+                }
+            },
+            CodeBlockPopoverMsg::Close => self.is_open = false,
+            CodeBlockPopoverMsg::SetLanguage(language) => self.language = language,
+            CodeBlockPopoverMsg::Submit => {
+                if let Some(textarea) = Self::textarea(ctx) {
+                    let language = self.language.clone();
+                    let in_block = self.in_block;
+                    edit_textarea(&textarea, |state| {
+                        if in_block {
+                            set_code_language(state, &language);
+                        } else {
+                            toggle_code_block(state, &language);
+                        }
+                    });
+                }
+                self.is_open = false;
+            },
+            CodeBlockPopoverMsg::Remove => {
+                if let Some(textarea) = Self::textarea(ctx) {
+                    edit_textarea(&textarea, |state| {
+                        if let Some(block) = current_code_block(state) {
+                            remove_code_block(state, &block);
+                        }
+                    });
+                }
+                self.is_open = false;
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onclick = ctx.link().callback({
+            let is_open = self.is_open;
+            move |_: MouseEvent| {
+                if is_open {
+                    CodeBlockPopoverMsg::Close
+                } else {
+                    CodeBlockPopoverMsg::Open
+                }
+            }
+        });
+
+        html! {
+            <span class = "lew-simple__popover_anchor">
+                <button class = { props.class.clone() } style = { format!("width: {0}; height: {0}", props.size) }
+                        type = "button" title = { props.title.clone() } { onclick }>
+                    { BuiltinAction::CodeBlock.icon(props.size) }
+                </button>
+                if self.is_open {
+                    { self.view_popover(ctx) }
+                }
+            </span>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn code_blocks_find_fences_and_content() {
+        let text = chars("a\n```rust\nfn a() {}\n```\nb");
+        let blocks = code_blocks(&text);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].info, "rust");
+        assert_eq!(blocks[0].opening, Selection::new(2, 9));
+        assert_eq!(blocks[0].content, Selection::new(10, 19));
+        assert_eq!(blocks[0].closing, Some(Selection::new(20, 23)));
+    }
+
+    #[test]
+    fn code_blocks_close_only_on_matching_fences() {
+        let text = chars("````\n```\n~~~\n````");
+        let blocks = code_blocks(&text);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].closing, Some(Selection::new(13, 17)));
+
+        let blocks = code_blocks(&chars("~~~\ncode"));
+        assert_eq!(blocks[0].closing, None);
+        assert!(code_blocks(&chars("``inline``")).is_empty());
+    }
+
+    #[test]
+    fn fence_for_outgrows_content_backticks() {
+        assert_eq!(fence_for("code"), "```");
+        assert_eq!(fence_for("a ``` b"), "````");
+    }
+
+    #[test]
+    fn toggle_code_block_wraps_and_unwraps_lines() {
+        let mut state = EditorState::new("a\nb", 0..3);
+        toggle_code_block(&mut state, "rust");
+        assert_eq!(state.text, "```rust\na\nb\n```");
+        assert_eq!(state.selected_text(), "a\nb");
+
+        toggle_code_block(&mut state, "");
+        assert_eq!(state, EditorState::new("a\nb", 0..3));
+    }
+
+    #[test]
+    fn set_code_language_replaces_info_string() {
+        let mut state = EditorState::new("```rust\na\n```", 9..9);
+        assert!(set_code_language(&mut state, "toml"));
+        assert_eq!(state.text, "```toml\na\n```");
+        assert!(!set_code_language(&mut state, "a`b"));
+    }
+}
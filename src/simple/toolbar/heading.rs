@@ -0,0 +1,252 @@
+use std::iter;
+
+use super::list::{line_bounds, selected_lines, ListItem};
+use super::{EditorState, Selection};
+
+pub const MAX_HEADING_LEVEL: usize = 6;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeadingStyle {
+    #[default]
+    Atx,
+    Setext,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    pub level: usize,
+    pub style: HeadingStyle,
+    pub content: Selection,
+    pub full: Selection,
+}
+
+impl Heading {
+    pub fn parse(text: &[char], line: Selection) -> Option<Self> {
+        let line_text = &text[line.start..line.end];
+        if let Some((level, content)) = parse_atx(line_text) {
+            return Some(Self {
+                level,
+                style: HeadingStyle::Atx,
+                content: Selection::from(line.start + content.start..line.start + content.end),
+                full: line,
+            });
+        }
+
+        let (start, end) = trim_indent(line_text)?;
+        let is_paragraph = {
+            let content: String = line_text[start..end].iter().collect();
+            ListItem::parse(&content).is_none() && !content.starts_with('#')
+        };
+        if !is_paragraph || line.end >= text.len() {
+            return None;
+        }
+
+        let underline = line_bounds(text, line.end + 1);
+        let level = setext_underline_level(&text[underline.start..underline.end])?;
+        Some(Self {
+            level,
+            style: HeadingStyle::Setext,
+            content: Selection::from(line.start + start..line.start + end),
+            full: Selection::from(line.start..underline.end),
+        })
+    }
+}
+
+fn trim_indent(line: &[char]) -> Option<(usize, usize)> {
+    let start = line.iter().take_while(|&&ch| ch == ' ').count();
+    let end = line.len() - line.iter().rev().take_while(|ch| ch.is_whitespace()).count();
+    (start <= 3 && start < end).then_some((start, end))
+}
+
+fn parse_atx(line: &[char]) -> Option<(usize, Selection)> {
+    let indent = line.iter().take_while(|&&ch| ch == ' ').count();
+    if indent > 3 {
+        return None;
+    }
+
+    let level = line[indent..].iter().take_while(|&&ch| ch == '#').count();
+    let marker_end = indent + level;
+    if !(1..=MAX_HEADING_LEVEL).contains(&level) || line.get(marker_end).is_some_and(|&ch| ch != ' ' && ch != '\t') {
+        return None;
+    }
+
+    let start = marker_end + line[marker_end..].iter().take_while(|ch| ch.is_whitespace()).count();
+    let mut end = line.len() - line[start..].iter().rev().take_while(|ch| ch.is_whitespace()).count();
+    let closing = line[start..end].iter().rev().take_while(|&&ch| ch == '#').count();
+    if closing > 0 && (end - closing == start || line[end - closing - 1].is_whitespace()) {
+        end -= closing;
+        end -= line[start..end]
+            .iter()
+            .rev()
+            .take_while(|ch| ch.is_whitespace())
+            .count();
+    }
+    Some((level, Selection::from(start..end)))
+}
+
+fn setext_underline_level(line: &[char]) -> Option<usize> {
+    let (start, end) = trim_indent(line)?;
+    let underline = &line[start..end];
+    if underline.iter().all(|&ch| ch == '=') {
+        Some(1)
+    } else if underline.iter().all(|&ch| ch == '-') {
+        Some(2)
+    } else {
+        None
+    }
+}
+
+fn heading_lines(text: &[char], selection: Selection) -> Vec<Selection> {
+    let mut lines = selected_lines(text, selection);
+    if let Some(first) = lines.first().copied().filter(|line| line.start > 0) {
+        let previous = line_bounds(text, first.start - 1);
+        if Heading::parse(text, previous).is_some_and(|heading| heading.full.end == first.end) {
+            lines.insert(0, previous);
+        }
+    }
+
+    let mut result: Vec<Selection> = Vec::with_capacity(lines.len());
+    for line in lines {
+        let is_underline = result.last().is_some_and(|&previous| {
+            Heading::parse(text, previous).is_some_and(|heading| heading.full.end == line.end)
+        });
+        if !is_underline {
+            result.push(line);
+        }
+    }
+    result
+}
+
+pub fn current_heading(state: &EditorState) -> Option<Heading> {
+    let text: Vec<char> = state.text.chars().collect();
+    let line = *heading_lines(&text, state.char_selection()).first()?;
+    Heading::parse(&text, line)
+}
+
+pub fn set_heading(state: &mut EditorState, level: Option<usize>, style: HeadingStyle) -> bool {
+    let mut text: Vec<char> = state.text.chars().collect();
+    let mut selection = state.char_selection();
+    let lines = heading_lines(&text, selection);
+    let is_multiline = lines.len() > 1;
+    let level = level.map(|level| level.clamp(1, MAX_HEADING_LEVEL));
+    let mut changed = false;
+
+    for line in lines.into_iter().rev() {
+        let (full, content) = match Heading::parse(&text, line) {
+            Some(heading) => (heading.full, heading.content),
+            None => (line, line),
+        };
+        if is_multiline && text[content.start..content.end].iter().all(|ch| ch.is_whitespace()) {
+            continue;
+        }
+
+        let content_text: Vec<char> = text[content.start..content.end].to_vec();
+        let (replacement, content_offset): (Vec<char>, usize) = match level {
+            None => (content_text, 0),
+            Some(level) if style == HeadingStyle::Setext && level <= 2 && !content_text.is_empty() => {
+                let underline = if level == 1 { '=' } else { '-' };
+                let underline_len = content_text.len().max(3);
+                let replacement = content_text
+                    .iter()
+                    .copied()
+                    .chain(iter::once('\n'))
+                    .chain(iter::repeat_n(underline, underline_len))
+                    .collect();
+                (replacement, 0)
+            },
+            Some(level) => {
+                let replacement = iter::repeat_n('#', level)
+                    .chain(iter::once(' '))
+                    .chain(content_text.iter().copied())
+                    .collect();
+                (replacement, level + 1)
+            },
+        };
+        if text[full.start..full.end] == replacement[..] {
+            continue;
+        }
+
+        let new_content_start = full.start + content_offset;
+        let new_content_end = new_content_start + content.len();
+        let new_end = full.start + replacement.len();
+        let shift = |pos: usize| {
+            if pos < full.start {
+                pos
+            } else if pos > full.end {
+                pos - full.end + new_end
+            } else if pos < content.start {
+                new_content_start
+            } else if pos <= content.end {
+                new_content_start + (pos - content.start)
+            } else {
+                new_content_end
+            }
+        };
+        let start = if !selection.is_empty() && selection.start == full.start {
+            full.start
+        } else {
+            shift(selection.start)
+        };
+        selection = Selection::from(start..shift(selection.end));
+        text.splice(full.start..full.end, replacement);
+        changed = true;
+    }
+
+    if changed {
+        state.text = text.into_iter().collect();
+        state.selection = selection;
+    }
+    changed
+}
+
+pub fn cycle_heading(state: &mut EditorState, style: HeadingStyle) -> bool {
+    let (level, style) = match current_heading(state) {
+        Some(heading) if heading.level >= MAX_HEADING_LEVEL => (None, style),
+        Some(heading) => (Some(heading.level + 1), heading.style),
+        None => (Some(1), style),
+    };
+    set_heading(state, level, style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycled(text: &str, style: HeadingStyle) -> String {
+        let mut state = EditorState::new(text, 0..0);
+        cycle_heading(&mut state, style);
+        state.text
+    }
+
+    fn with_level(text: &str, level: Option<usize>, style: HeadingStyle) -> String {
+        let mut state = EditorState::new(text, 0..text.chars().count());
+        set_heading(&mut state, level, style);
+        state.text
+    }
+
+    #[test]
+    fn cycle_heading_steps_through_levels() {
+        assert_eq!(cycled("Title", HeadingStyle::Atx), "# Title");
+        assert_eq!(cycled("# Title", HeadingStyle::Atx), "## Title");
+        assert_eq!(cycled("###### Title", HeadingStyle::Atx), "Title");
+    }
+
+    #[test]
+    fn set_heading_replaces_existing_markers() {
+        assert_eq!(with_level("### Title", Some(1), HeadingStyle::Atx), "# Title");
+        assert_eq!(with_level("Title\n=====", Some(3), HeadingStyle::Atx), "### Title");
+        assert_eq!(with_level("## Title ##", None, HeadingStyle::Atx), "Title");
+    }
+
+    #[test]
+    fn set_heading_writes_setext_underlines() {
+        assert_eq!(with_level("Title", Some(1), HeadingStyle::Setext), "Title\n=====");
+        assert_eq!(with_level("# Hi", Some(2), HeadingStyle::Setext), "Hi\n---");
+        assert_eq!(with_level("Title", Some(3), HeadingStyle::Setext), "### Title");
+    }
+
+    #[test]
+    fn set_heading_skips_blank_lines_in_selection() {
+        assert_eq!(with_level("a\n\nb", Some(1), HeadingStyle::Atx), "# a\n\n# b");
+    }
+}
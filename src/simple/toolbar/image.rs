@@ -0,0 +1,133 @@
+use web_sys::HtmlInputElement;
+use yew::{
+    function_component, html, use_context, use_node_ref, Callback, Event, Html, MouseEvent, Properties, TargetCast,
+};
+
+use super::tool::{BuiltinAction, EditorAction};
+use super::{find_textarea, EditorContext};
+use crate::upload::{upload_files, UploadError, Uploader, UploaderRef};
+use crate::Widget;
+
+#[derive(Debug, Clone)]
+pub struct ImageUploadTool {
+    pub textarea_selector: String,
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub accept: String,
+    pub uploader: UploaderRef,
+    pub onerror: Callback<UploadError>,
+}
+
+impl ImageUploadTool {
+    pub fn new(uploader: impl Uploader + 'static) -> Self {
+        Self {
+            textarea_selector: ".lew-simple__textarea".to_string(),
+            class: "lew-simple__tool_button".to_string(),
+            title: "Upload image".to_string(),
+            size: 16,
+            accept: "image/*".to_string(),
+            uploader: UploaderRef::new(uploader),
+            onerror: Callback::noop(),
+        }
+    }
+
+    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
+        self.textarea_selector = selector.into();
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_accept(mut self, accept: impl Into<String>) -> Self {
+        self.accept = accept.into();
+        self
+    }
+
+    pub fn with_onerror(mut self, onerror: Callback<UploadError>) -> Self {
+        self.onerror = onerror;
+        self
+    }
+}
+
+impl Widget for ImageUploadTool {
+    fn build(&self) -> Html {
+        html! {
+            <ImageUploadButton class = { self.class.clone() } title = { self.title.clone() } size = { self.size }
+                    textarea_selector = { self.textarea_selector.clone() } accept = { self.accept.clone() }
+                    uploader = { self.uploader.clone() } onerror = { self.onerror.clone() } />
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ImageUploadButtonProps {
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub textarea_selector: String,
+    pub accept: String,
+    pub uploader: UploaderRef,
+    pub onerror: Callback<UploadError>,
+}
+
+#[function_component(ImageUploadButton)]
+pub fn image_upload_button(props: &ImageUploadButtonProps) -> Html {
+    let context = use_context::<EditorContext>();
+    let input = use_node_ref();
+
+    let onclick = {
+        let input = input.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Some(input) = input.cast::<HtmlInputElement>() {
+                input.click();
+            }
+        })
+    };
+
+    let onchange = {
+        let selector = props.textarea_selector.clone();
+        let uploader = props.uploader.clone();
+        let onerror = props.onerror.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            let Some(file_list) = input.files() else {
+                return;
+            };
+            let files = (0..file_list.length())
+                .filter_map(|index| file_list.get(index))
+                .collect();
+            let textarea = context
+                .as_ref()
+                .and_then(EditorContext::textarea)
+                .or_else(|| find_textarea(&selector));
+            if let Some(textarea) = textarea {
+                upload_files(textarea, files, uploader.clone(), onerror.clone());
+            }
+            input.set_value("");
+        })
+    };
+
+    html! {
+        <>
+            <button class = { props.class.clone() } style = { format!("width: {0}; height: {0}", props.size) }
+                    type = "button" title = { props.title.clone() } { onclick }>
+                { BuiltinAction::ImageLink.icon(props.size) }
+            </button>
+            <input ref = { input } type = "file" accept = { props.accept.clone() } multiple = true hidden = true { onchange } />
+        </>
+    }
+}
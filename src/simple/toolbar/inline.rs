@@ -0,0 +1,218 @@
+use std::ops::Range;
+
+pub fn is_escaped(text: &[char], pos: usize) -> bool {
+    text[..pos].iter().rev().take_while(|&&ch| ch == '\\').count() % 2 == 1
+}
+
+pub fn run_len_before(text: &[char], pos: usize, delimiter: char) -> usize {
+    text[..pos].iter().rev().take_while(|&&ch| ch == delimiter).count()
+}
+
+pub fn run_len_after(text: &[char], pos: usize, delimiter: char) -> usize {
+    text[pos..].iter().take_while(|&&ch| ch == delimiter).count()
+}
+
+pub fn code_spans(text: &[char]) -> Vec<Range<usize>> {
+    let mut spans = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        if text[pos] != '`' || is_escaped(text, pos) {
+            pos += 1;
+            continue;
+        }
+
+        let open_len = run_len_after(text, pos, '`');
+        let mut close = pos + open_len;
+        let mut found = None;
+        while close < text.len() {
+            if text[close] == '`' {
+                let close_len = run_len_after(text, close, '`');
+                if close_len == open_len {
+                    found = Some(close + close_len);
+                    break;
+                }
+                close += close_len;
+            } else if text[close] == '\n' && text.get(close + 1) == Some(&'\n') {
+                break;
+            } else {
+                close += 1;
+            }
+        }
+
+        match found {
+            Some(end) => {
+                spans.push(pos..end);
+                pos = end;
+            },
+            None => pos += open_len,
+        }
+    }
+    spans
+}
+
+pub fn is_code_span_delimiter(prefix: &str, suffix: &str) -> bool {
+    prefix == suffix && !prefix.is_empty() && prefix.chars().all(|ch| ch == '`')
+}
+
+pub fn code_span_fence(content: &[char]) -> (String, &'static str) {
+    let longest = content.split(|&ch| ch != '`').map(<[char]>::len).max().unwrap_or(0);
+    let is_blank = content.iter().all(|&ch| ch == ' ');
+    let needs_padding = content.first() == Some(&'`')
+        || content.last() == Some(&'`')
+        || (!is_blank && content.first() == Some(&' ') && content.last() == Some(&' '));
+    ("`".repeat(longest + 1), if needs_padding { " " } else { "" })
+}
+
+pub fn code_span_content(text: &[char], span: Range<usize>) -> Range<usize> {
+    let fence_len = run_len_after(text, span.start, '`');
+    let content = span.start + fence_len..span.end - fence_len;
+    let is_padded = content.len() >= 2
+        && text[content.start] == ' '
+        && text[content.end - 1] == ' '
+        && text[content.clone()].iter().any(|&ch| ch != ' ');
+    if is_padded {
+        content.start + 1..content.end - 1
+    } else {
+        content
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkSpan {
+    pub full: Range<usize>,
+    pub text: Range<usize>,
+    pub destination: Range<usize>,
+}
+
+pub fn link_spans(text: &[char]) -> Vec<LinkSpan> {
+    let code = code_spans(text);
+    let in_code = |pos: usize| code.iter().any(|span| span.contains(&pos));
+    let mut links = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        if text[pos] != '[' || is_escaped(text, pos) || in_code(pos) {
+            pos += 1;
+            continue;
+        }
+
+        let link = find_closing(text, pos, '[', ']')
+            .filter(|&close| text.get(close + 1) == Some(&'('))
+            .and_then(|close| find_closing(text, close + 1, '(', ')').map(|end| (close, end)));
+        match link {
+            Some((close, end)) => {
+                let start = if pos > 0 && text[pos - 1] == '!' { pos - 1 } else { pos };
+                links.push(LinkSpan {
+                    full: start..end + 1,
+                    text: pos + 1..close,
+                    destination: close + 2..end,
+                });
+                pos = end + 1;
+            },
+            None => pos += 1,
+        }
+    }
+    links
+}
+
+fn find_closing(text: &[char], open: usize, open_ch: char, close_ch: char) -> Option<usize> {
+    let mut depth = 0;
+    for (pos, &ch) in text.iter().enumerate().skip(open) {
+        if ch == '\n' && text.get(pos + 1) == Some(&'\n') {
+            return None;
+        }
+        if is_escaped(text, pos) {
+            continue;
+        }
+        if ch == open_ch {
+            depth += 1;
+        } else if ch == close_ch {
+            depth -= 1;
+            if depth == 0 {
+                return Some(pos);
+            }
+        }
+    }
+    None
+}
+
+pub fn emphasis_delimiter(prefix: &str, suffix: &str) -> Option<char> {
+    let delimiter = prefix.chars().next()?;
+    let is_delimiter_run = matches!(delimiter, '*' | '_' | '~' | '=' | '^')
+        && prefix == suffix
+        && prefix.chars().count() <= 2
+        && prefix.chars().all(|ch| ch == delimiter);
+    is_delimiter_run.then_some(delimiter)
+}
+
+pub fn has_emphasis_layer(delimiter: char, run_len: usize, layer_len: usize) -> bool {
+    match delimiter {
+        '*' | '_' if layer_len == 1 => run_len % 2 == 1,
+        '*' | '_' => run_len >= 2,
+        _ => run_len >= layer_len,
+    }
+}
+
+pub fn enclosing_inline(text: &[char], range: Range<usize>) -> Range<usize> {
+    let code = code_spans(text).into_iter().find(|span| {
+        span.start < range.end && range.start < span.end && span.start <= range.start && range.end <= span.end
+    });
+    if let Some(span) = code {
+        return span;
+    }
+
+    link_spans(text)
+        .into_iter()
+        .find(|link| {
+            let destination = link.destination.start - 1..link.destination.end + 1;
+            destination.start < range.end.max(range.start + 1)
+                && range.start < destination.end
+                && link.full.start <= range.start
+                && range.end <= link.full.end
+        })
+        .map(|link| link.full)
+        .unwrap_or(range)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn code_spans_match_equal_backtick_runs() {
+        assert_eq!(code_spans(&chars("a `b` c")), vec![2..5]);
+        assert_eq!(code_spans(&chars("``a ` b``")), vec![0..9]);
+        assert_eq!(code_spans(&chars("`a`` b")), Vec::<Range<usize>>::new());
+        assert_eq!(code_spans(&chars("\\`a` `b`")), vec![3..6]);
+        assert_eq!(code_spans(&chars("`a\n\nb`")), Vec::<Range<usize>>::new());
+    }
+
+    #[test]
+    fn code_span_fence_outgrows_content_backticks() {
+        assert_eq!(code_span_fence(&chars("a")), ("`".to_string(), ""));
+        assert_eq!(code_span_fence(&chars("a`b")), ("``".to_string(), ""));
+        assert_eq!(code_span_fence(&chars("a``b")), ("```".to_string(), ""));
+    }
+
+    #[test]
+    fn code_span_fence_pads_backticks_and_spaces_at_edges() {
+        assert_eq!(code_span_fence(&chars("`a")), ("``".to_string(), " "));
+        assert_eq!(code_span_fence(&chars("a`")), ("``".to_string(), " "));
+        assert_eq!(code_span_fence(&chars(" a ")), ("`".to_string(), " "));
+        assert_eq!(code_span_fence(&chars("  ")), ("`".to_string(), ""));
+        assert_eq!(code_span_fence(&chars(" a")), ("`".to_string(), ""));
+    }
+
+    #[test]
+    fn code_span_content_strips_one_padding_space() {
+        let text = chars("`` `a ``");
+        assert_eq!(code_span_content(&text, 0..text.len()), 3..5);
+        let text = chars("`  `");
+        assert_eq!(code_span_content(&text, 0..text.len()), 1..3);
+        let text = chars("`a`");
+        assert_eq!(code_span_content(&text, 0..text.len()), 1..2);
+    }
+}
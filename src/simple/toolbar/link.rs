@@ -0,0 +1,627 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::{
+    html, Callback, Component, Context, Html, InputEvent, KeyboardEvent, MouseEvent, Properties, SubmitEvent,
+    TargetCast,
+};
+
+use super::tool::{BuiltinAction, EditorAction};
+use super::{find_textarea, inline, EditorContext, EditorState, ReplaceFmt, Selection, UnselectedApplyMode};
+use crate::keymap::{Action, Shortcut};
+use crate::markup::normalize_url;
+use crate::Widget;
+
+pub const DEFAULT_LINK_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LinkDraft {
+    pub text: String,
+    pub url: String,
+    pub title: String,
+    pub range: Selection,
+}
+
+impl LinkDraft {
+    pub fn from_state(state: &EditorState) -> Self {
+        let text: Vec<char> = state.text.chars().collect();
+        let selection = state.char_selection().clamp(text.len());
+        let existing = inline::link_spans(&text).into_iter().find(|link| {
+            text[link.full.start] != '!' && link.full.start <= selection.start && selection.end <= link.full.end
+        });
+
+        if let Some(link) = existing {
+            let destination: String = text[link.destination].iter().collect();
+            let (url, title) = parse_destination(&destination);
+            return Self {
+                text: text[link.text].iter().collect(),
+                url,
+                title,
+                range: link.full.into(),
+            };
+        }
+
+        let selected: String = text[selection.start..selection.end].iter().collect();
+        if looks_like_url(&selected) {
+            Self {
+                url: selected.trim().to_string(),
+                range: selection,
+                ..Self::default()
+            }
+        } else {
+            Self {
+                text: selected,
+                range: selection,
+                ..Self::default()
+            }
+        }
+    }
+
+    pub fn destination(&self) -> String {
+        let url: String = self
+            .url
+            .trim()
+            .chars()
+            .map(|ch| match ch {
+                '<' => "%3C".to_string(),
+                '>' => "%3E".to_string(),
+                ch => ch.to_string(),
+            })
+            .collect();
+        let url = if url.contains(|ch: char| ch.is_whitespace() || ch == '(' || ch == ')') {
+            format!("<{url}>")
+        } else {
+            url
+        };
+
+        let title = self.title.trim();
+        if title.is_empty() {
+            url
+        } else {
+            format!("{url} \"{}\"", title.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkError {
+    EmptyUrl,
+    InvalidUrl,
+    UnsupportedScheme(String),
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LinkError::EmptyUrl => f.write_str("URL is required"),
+            LinkError::InvalidUrl => f.write_str("URL must not contain line breaks"),
+            LinkError::UnsupportedScheme(scheme) => write!(f, "Unsupported URL scheme \"{scheme}\""),
+        }
+    }
+}
+
+pub fn looks_like_url(text: &str) -> bool {
+    let text = text.trim();
+    !text.is_empty()
+        && !text.contains(char::is_whitespace)
+        && (text.starts_with("http://")
+            || text.starts_with("https://")
+            || text.starts_with("mailto:")
+            || text.starts_with("www."))
+}
+
+pub fn url_scheme(url: &str) -> Option<&str> {
+    let end = url.find([':', '/', '?', '#'])?;
+    let scheme = &url[..end];
+    let is_scheme = url[end..].starts_with(':')
+        && scheme.starts_with(|ch: char| ch.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '+' | '-' | '.'));
+    is_scheme.then_some(scheme)
+}
+
+pub fn validate_url(url: &str, schemes: &[impl AsRef<str>]) -> Result<String, LinkError> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err(LinkError::EmptyUrl);
+    }
+    if url.contains(['\n', '\r']) {
+        return Err(LinkError::InvalidUrl);
+    }
+    if url.starts_with("www.") {
+        return Ok(format!("https://{url}"));
+    }
+
+    match url_scheme(&normalize_url(url)) {
+        Some(scheme)
+            if !schemes
+                .iter()
+                .any(|allowed| allowed.as_ref().eq_ignore_ascii_case(scheme)) =>
+        {
+            Err(LinkError::UnsupportedScheme(scheme.to_string()))
+        },
+        _ => Ok(url.to_string()),
+    }
+}
+
+pub fn apply_link(state: &mut EditorState, draft: &LinkDraft) {
+    let link_text = if draft.text.trim().is_empty() {
+        draft.url.trim()
+    } else {
+        draft.text.as_str()
+    };
+    let link_text = escape_link_text(link_text);
+
+    let mut text: Vec<char> = state.text.chars().collect();
+    let range = draft.range.clamp(text.len());
+    let link_text_len = link_text.chars().count();
+    text.splice(range.start..range.end, link_text.chars());
+    state.text = text.into_iter().collect();
+    state.selection = Selection::new(range.start, range.start + link_text_len);
+
+    let suffix = format!("]({})", draft.destination());
+    let suffix_len = suffix.chars().count();
+    state.replace(ReplaceFmt::Around("[".to_string(), suffix), UnselectedApplyMode::Word);
+    let end = state.char_selection().end + suffix_len;
+    state.selection = Selection::new(end, end);
+}
+
+fn escape_link_text(text: &str) -> String {
+    let mut depth = 0_i32;
+    let balanced = text.chars().all(|ch| {
+        match ch {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => (),
+        }
+        depth >= 0
+    }) && depth == 0;
+    if balanced {
+        text.replace('\n', " ")
+    } else {
+        text.replace('[', "\\[").replace(']', "\\]").replace('\n', " ")
+    }
+}
+
+fn parse_destination(destination: &str) -> (String, String) {
+    let destination = destination.trim();
+    let (url, rest) = match destination.strip_prefix('<').and_then(|rest| rest.split_once('>')) {
+        Some((url, rest)) => (url, rest),
+        None => destination.split_once(char::is_whitespace).unwrap_or((destination, "")),
+    };
+
+    let rest = rest.trim();
+    let title = match rest.chars().next() {
+        Some(quote @ ('"' | '\'')) if rest.len() >= 2 && rest.ends_with(quote) => rest[1..rest.len() - 1]
+            .replace(&format!("\\{quote}"), &quote.to_string())
+            .replace("\\\\", "\\"),
+        Some('(') if rest.len() >= 2 && rest.ends_with(')') => rest[1..rest.len() - 1].to_string(),
+        _ => String::new(),
+    };
+    (url.to_string(), title)
+}
+
+#[derive(Clone, Default)]
+pub struct LinkOpener(Rc<RefCell<Option<Callback<()>>>>);
+
+impl LinkOpener {
+    pub fn open(&self) {
+        let open = self.0.borrow().clone();
+        if let Some(open) = open {
+            open.emit(());
+        }
+    }
+
+    fn set(&self, open: Option<Callback<()>>) {
+        *self.0.borrow_mut() = open;
+    }
+}
+
+impl PartialEq for LinkOpener {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for LinkOpener {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LinkOpener")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LinkTool {
+    pub textarea_selector: String,
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub shortcut: Option<Shortcut>,
+    pub schemes: Vec<String>,
+    pub opener: LinkOpener,
+}
+
+impl Default for LinkTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkTool {
+    pub fn new() -> Self {
+        Self {
+            textarea_selector: ".lew-simple__textarea".to_string(),
+            class: "lew-simple__tool_button".to_string(),
+            title: BuiltinAction::Link.title().to_string(),
+            size: 16,
+            shortcut: BuiltinAction::Link.shortcut(),
+            schemes: DEFAULT_LINK_SCHEMES.iter().map(|scheme| scheme.to_string()).collect(),
+            opener: LinkOpener::default(),
+        }
+    }
+
+    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
+        self.textarea_selector = selector.into();
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_shortcut(mut self, shortcut: impl Into<Option<Shortcut>>) -> Self {
+        self.shortcut = shortcut.into();
+        self
+    }
+
+    pub fn with_schemes(mut self, schemes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.schemes = schemes.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+impl Widget for LinkTool {
+    fn build(&self) -> Html {
+        let title = match &self.shortcut {
+            Some(shortcut) => format!("{} ({})", self.title, shortcut),
+            None => self.title.clone(),
+        };
+        html! {
+            <LinkPopover class = { self.class.clone() } { title } size = { self.size }
+                    textarea_selector = { self.textarea_selector.clone() } schemes = { self.schemes.clone() }
+                    opener = { self.opener.clone() } />
+        }
+    }
+
+    fn key_binding(&self) -> Option<(Shortcut, Action)> {
+        let opener = self.opener.clone();
+        let action: Action = Rc::new(move |_: &mut EditorState| opener.open());
+        self.shortcut.clone().map(|shortcut| (shortcut, action))
+    }
+}
+
+pub enum LinkPopoverMsg {
+    Open,
+    Close,
+    SetText(String),
+    SetUrl(String),
+    SetTitle(String),
+    Submit,
+}
+
+pub struct LinkPopover {
+    draft: Option<LinkDraft>,
+    error: Option<LinkError>,
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct LinkPopoverProps {
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub textarea_selector: String,
+
+    #[prop_or_default]
+    pub opener: LinkOpener,
+
+    #[prop_or(DEFAULT_LINK_SCHEMES.iter().map(|scheme| scheme.to_string()).collect())]
+    pub schemes: Vec<String>,
+}
+
+impl LinkPopover {
+    fn textarea(ctx: &Context<Self>) -> Option<HtmlTextAreaElement> {
+        ctx.link()
+            .context::<EditorContext>(Callback::noop())
+            .and_then(|(context, _)| context.textarea())
+            .or_else(|| find_textarea(&ctx.props().textarea_selector))
+    }
+
+    fn view_popover(&self, ctx: &Context<Self>, draft: &LinkDraft) -> Html {
+        let onsubmit = ctx.link().callback(|event: SubmitEvent| {
+            event.prevent_default();
+            LinkPopoverMsg::Submit
+        });
+        let onkeydown = ctx.link().batch_callback(|event: KeyboardEvent| {
+            (event.key() == "Escape").then(|| {
+                event.prevent_default();
+                LinkPopoverMsg::Close
+            })
+        });
+        let input_value = |event: InputEvent| event.target_unchecked_into::<HtmlInputElement>().value();
+
+        html! {
+            <form class = "lew-simple__popover" { onsubmit } { onkeydown }>
+                <label class = "lew-simple__popover_field">
+                    { "Text" }
+                    <input type = "text" value = { draft.text.clone() }
+                            oninput = { ctx.link().callback(move |event| LinkPopoverMsg::SetText(input_value(event))) } />
+                </label>
+                <label class = "lew-simple__popover_field">
+                    { "URL" }
+                    <input type = "text" value = { draft.url.clone() } placeholder = "https://"
+                            oninput = { ctx.link().callback(move |event| LinkPopoverMsg::SetUrl(input_value(event))) } />
+                </label>
+                <label class = "lew-simple__popover_field">
+                    { "Title" }
+                    <input type = "text" value = { draft.title.clone() }
+                            oninput = { ctx.link().callback(move |event| LinkPopoverMsg::SetTitle(input_value(event))) } />
+                </label>
+                if let Some(error) = &self.error {
+                    <div class = "lew-simple__popover_error">{ error.to_string() }</div>
+                }
+                <div class = "lew-simple__popover_actions">
+                    <button type = "submit">{ "Apply" }</button>
+                    <button type = "button" onclick = { ctx.link().callback(|_: MouseEvent| LinkPopoverMsg::Close) }>
+                        { "Cancel" }
+                    </button>
+                </div>
+            </form>
+        }
+    }
+}
+
+impl Component for LinkPopover {
+    type Message = LinkPopoverMsg;
+    type Properties = LinkPopoverProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.props()
+            .opener
+            .set(Some(ctx.link().callback(|()| LinkPopoverMsg::Open)));
+        Self {
+            draft: None,
+            error: None,
+        }
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().opener != old_props.opener {
+            ctx.props()
+                .opener
+                .set(Some(ctx.link().callback(|()| LinkPopoverMsg::Open)));
+        }
+        true
+    }
+
+    fn destroy(&mut self, ctx: &Context<Self>) {
+        ctx.props().opener.set(None);
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            LinkPopoverMsg::Open => {
+                self.draft =
+                    Self::textarea(ctx).map(|textarea| LinkDraft::from_state(&EditorState::from_textarea(&textarea)));
+                self.error = None;
+            },
+            LinkPopoverMsg::Close => {
+                self.draft = None;
+                self.error = None;
+            },
+            LinkPopoverMsg::SetText(text) => {
+                if let Some(draft) = &mut self.draft {
+                    draft.text = text;
+                }
+            },
+            LinkPopoverMsg::SetUrl(url) => {
+                if let Some(draft) = &mut self.draft {
+                    draft.url = url;
+                }
+                self.error = None;
+            },
+            LinkPopoverMsg::SetTitle(title) => {
+                if let Some(draft) = &mut self.draft {
+                    draft.title = title;
+                }
+            },
+            LinkPopoverMsg::Submit => {
+                let (Some(draft), Some(textarea)) = (&mut self.draft, Self::textarea(ctx)) else {
+                    return false;
+                };
+                match validate_url(&draft.url, &ctx.props().schemes) {
+                    Ok(url) => {
+                        draft.url = url;
+                        let mut state = EditorState::from_textarea(&textarea);
+                        apply_link(&mut state, draft);
+                        state.apply_to_textarea(&textarea);
+                        self.draft = None;
+                        self.error = None;
+                    },
+                    Err(error) => self.error = Some(error),
+                }
+            },
+        }
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onclick = ctx.link().callback({
+            let is_open = self.draft.is_some();
+            move |_: MouseEvent| {
+                if is_open {
+                    LinkPopoverMsg::Close
+                } else {
+                    LinkPopoverMsg::Open
+                }
+            }
+        });
+
+        html! {
+            <span class = "lew-simple__popover_anchor">
+                <button class = { props.class.clone() } style = { format!("width: {0}; height: {0}", props.size) }
+                        type = "button" title = { props.title.clone() } { onclick }>
+                    { BuiltinAction::Link.icon(props.size) }
+                </button>
+                if let Some(draft) = &self.draft {
+                    { self.view_popover(ctx, draft) }
+                }
+            </span>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unsupported(scheme: &str) -> Result<String, LinkError> {
+        Err(LinkError::UnsupportedScheme(scheme.to_string()))
+    }
+
+    #[test]
+    fn looks_like_url_accepts_known_prefixes() {
+        assert!(looks_like_url("https://a.b"));
+        assert!(looks_like_url(" www.a.b "));
+        assert!(looks_like_url("mailto:a@b"));
+        assert!(!looks_like_url("a b"));
+        assert!(!looks_like_url("ftp://a.b"));
+        assert!(!looks_like_url("https://a b"));
+    }
+
+    #[test]
+    fn validate_url_checks_schemes() {
+        assert_eq!(validate_url(" ", DEFAULT_LINK_SCHEMES), Err(LinkError::EmptyUrl));
+        assert_eq!(validate_url("a\nb", DEFAULT_LINK_SCHEMES), Err(LinkError::InvalidUrl));
+        assert_eq!(
+            validate_url("www.a.b", DEFAULT_LINK_SCHEMES),
+            Ok("https://www.a.b".to_string())
+        );
+        assert_eq!(
+            validate_url("HTTPS://a.b", DEFAULT_LINK_SCHEMES),
+            Ok("HTTPS://a.b".to_string())
+        );
+        assert_eq!(
+            validate_url("/docs?a=b:c", DEFAULT_LINK_SCHEMES),
+            Ok("/docs?a=b:c".to_string())
+        );
+        assert_eq!(
+            validate_url("javascript:alert(1)", DEFAULT_LINK_SCHEMES),
+            unsupported("javascript")
+        );
+        assert_eq!(validate_url("ftp://a.b", &["ftp"]), Ok("ftp://a.b".to_string()));
+    }
+
+    #[test]
+    fn validate_url_normalizes_before_reading_the_scheme() {
+        for url in [
+            "javascript&#58;alert(1)",
+            "&#106;avascript:alert(1)",
+            "java\tscript:alert(1)",
+            "java\u{0}script:alert(1)",
+            "javascript&colon;alert(1)",
+        ] {
+            assert_eq!(
+                validate_url(url, DEFAULT_LINK_SCHEMES),
+                unsupported("javascript"),
+                "{url:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_destination_reads_titles_and_angle_brackets() {
+        assert_eq!(parse_destination("https://a"), ("https://a".to_string(), String::new()));
+        assert_eq!(
+            parse_destination("https://a \"A \\\"b\\\"\""),
+            ("https://a".to_string(), "A \"b\"".to_string())
+        );
+        assert_eq!(parse_destination("<a b> 'c'"), ("a b".to_string(), "c".to_string()));
+        assert_eq!(parse_destination("a (c)"), ("a".to_string(), "c".to_string()));
+    }
+
+    #[test]
+    fn escape_link_text_escapes_unbalanced_brackets() {
+        assert_eq!(escape_link_text("a [b] c"), "a [b] c");
+        assert_eq!(escape_link_text("a ]b["), "a \\]b\\[");
+        assert_eq!(escape_link_text("a\nb"), "a b");
+    }
+
+    #[test]
+    fn draft_from_caret_inside_a_link() {
+        let state = EditorState::new("see [docs](https://d \"T\") now", 6..6);
+        assert_eq!(
+            LinkDraft::from_state(&state),
+            LinkDraft {
+                text: "docs".to_string(),
+                url: "https://d".to_string(),
+                title: "T".to_string(),
+                range: Selection::new(4, 25),
+            }
+        );
+    }
+
+    #[test]
+    fn draft_from_selected_url() {
+        let state = EditorState::new("go https://x.y", 3..14);
+        assert_eq!(
+            LinkDraft::from_state(&state),
+            LinkDraft {
+                url: "https://x.y".to_string(),
+                range: Selection::new(3, 14),
+                ..LinkDraft::default()
+            }
+        );
+    }
+
+    #[test]
+    fn apply_link_wraps_the_draft_range() {
+        let mut state = EditorState::new("a b c", 2..3);
+        let mut draft = LinkDraft::from_state(&state);
+        draft.url = "https://x".to_string();
+        apply_link(&mut state, &draft);
+        assert_eq!(state, EditorState::new("a [b](https://x) c", 16..16));
+
+        let mut state = EditorState::new("see [docs](https://d) now", 6..6);
+        let mut draft = LinkDraft::from_state(&state);
+        draft.url = "https://e f".to_string();
+        draft.title = "T".to_string();
+        apply_link(&mut state, &draft);
+        assert_eq!(state.text, "see [docs](<https://e f> \"T\") now");
+    }
+
+    #[test]
+    fn apply_link_uses_url_for_empty_text() {
+        let mut state = EditorState::new("a ", 2..2);
+        let draft = LinkDraft {
+            url: "https://x".to_string(),
+            range: Selection::new(2, 2),
+            ..LinkDraft::default()
+        };
+        apply_link(&mut state, &draft);
+        assert_eq!(state.text, "a [https://x](https://x)");
+    }
+}
@@ -0,0 +1,887 @@
+use std::fmt;
+
+use super::code::{code_blocks, current_code_block, CodeBlock};
+use super::{EditorState, Selection};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListMarker {
+    Bullet(char),
+    Ordered(u64, char),
+    Task(char, bool),
+    Quote,
+}
+
+impl ListMarker {
+    pub fn next(&self) -> Self {
+        match *self {
+            ListMarker::Ordered(number, delimiter) => ListMarker::Ordered(number + 1, delimiter),
+            ListMarker::Task(bullet, _) => ListMarker::Task(bullet, false),
+            marker => marker,
+        }
+    }
+
+    pub fn is_list(&self) -> bool {
+        !matches!(self, ListMarker::Quote)
+    }
+
+    pub fn content_offset(&self) -> usize {
+        match self {
+            ListMarker::Task(..) => 2,
+            marker => marker.to_string().chars().count(),
+        }
+    }
+}
+
+impl fmt::Display for ListMarker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ListMarker::Bullet(bullet) => write!(f, "{bullet} "),
+            ListMarker::Ordered(number, delimiter) => write!(f, "{number}{delimiter} "),
+            ListMarker::Task(bullet, checked) => write!(f, "{bullet} [{}] ", if *checked { 'x' } else { ' ' }),
+            ListMarker::Quote => f.write_str("> "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListItem {
+    pub indent: String,
+    pub marker: ListMarker,
+    pub marker_len: usize,
+}
+
+impl ListItem {
+    pub fn parse(line: &str) -> Option<Self> {
+        if is_thematic_break(line) {
+            return None;
+        }
+
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let (indent, rest) = line.split_at(indent_len);
+
+        let (marker, len) = if let Some(content) = rest.strip_prefix('>') {
+            (ListMarker::Quote, 1 + content.starts_with(' ') as usize)
+        } else if let Some(bullet) = rest.chars().next().filter(|ch| matches!(ch, '-' | '*' | '+')) {
+            let content = &rest[1..];
+            if !(content.is_empty() || content.starts_with(' ')) {
+                return None;
+            }
+            match parse_task_box(content) {
+                Some((checked, len)) => (ListMarker::Task(bullet, checked), 1 + len),
+                None => (ListMarker::Bullet(bullet), 1 + content.starts_with(' ') as usize),
+            }
+        } else {
+            let digits = rest.chars().take_while(char::is_ascii_digit).count();
+            if digits == 0 || digits > 9 {
+                return None;
+            }
+            let delimiter = rest[digits..].chars().next().filter(|ch| matches!(ch, '.' | ')'))?;
+            let content = &rest[digits + 1..];
+            if !(content.is_empty() || content.starts_with(' ')) {
+                return None;
+            }
+            let number = rest[..digits].parse().ok()?;
+            (
+                ListMarker::Ordered(number, delimiter),
+                digits + 1 + content.starts_with(' ') as usize,
+            )
+        };
+
+        Some(Self {
+            indent: indent.to_string(),
+            marker,
+            marker_len: indent.chars().count() + len,
+        })
+    }
+
+    pub fn prefix(&self) -> String {
+        format!("{}{}", self.indent, self.marker)
+    }
+}
+
+pub fn is_thematic_break(line: &str) -> bool {
+    let rest = line.trim_start_matches(' ');
+    if line.len() - rest.len() > 3 {
+        return false;
+    }
+
+    let mut marks = rest.chars().filter(|ch| !matches!(ch, ' ' | '\t'));
+    let Some(mark) = marks.next().filter(|ch| matches!(ch, '-' | '*' | '_')) else {
+        return false;
+    };
+    let mut count = 1;
+    for ch in marks {
+        if ch != mark {
+            return false;
+        }
+        count += 1;
+    }
+    count >= 3
+}
+
+fn parse_task_box(content: &str) -> Option<(bool, usize)> {
+    let rest = content.strip_prefix(' ')?;
+    let checked = match rest.get(..3)? {
+        "[ ]" => false,
+        "[x]" | "[X]" => true,
+        _ => return None,
+    };
+    let rest = &rest[3..];
+    if rest.is_empty() {
+        Some((checked, 4))
+    } else if rest.starts_with(' ') {
+        Some((checked, 5))
+    } else {
+        None
+    }
+}
+
+pub fn line_bounds(text: &[char], pos: usize) -> Selection {
+    Selection::new(
+        text[..pos]
+            .iter()
+            .rposition(|&ch| ch == '\n')
+            .map(|pos| pos + 1)
+            .unwrap_or(0),
+        text[pos..]
+            .iter()
+            .position(|&ch| ch == '\n')
+            .map(|offset| pos + offset)
+            .unwrap_or(text.len()),
+    )
+}
+
+pub fn continue_list(state: &mut EditorState) -> bool {
+    let selection = state.char_selection();
+    if !selection.is_empty() || current_code_block(state).is_some() {
+        return false;
+    }
+
+    let mut text: Vec<char> = state.text.chars().collect();
+    let caret = selection.start.min(text.len());
+    let line = line_bounds(&text, caret);
+    let line_text: String = text[line.start..line.end].iter().collect();
+    let Some(item) = ListItem::parse(&line_text) else {
+        return false;
+    };
+
+    let content_start = line.start + item.marker_len;
+    if caret < content_start {
+        return false;
+    }
+
+    if line_text.chars().skip(item.marker_len).all(char::is_whitespace) {
+        text.drain(line.start..line.end);
+        state.text = text.into_iter().collect();
+        state.selection = Selection::new(line.start, line.start);
+    } else {
+        let continuation: Vec<char> = format!("\n{}{}", item.indent, item.marker.next()).chars().collect();
+        let new_caret = caret + continuation.len();
+        text.splice(caret..caret, continuation);
+        state.text = text.into_iter().collect();
+        state.selection = Selection::new(new_caret, new_caret);
+    }
+    true
+}
+
+pub fn selected_lines(text: &[char], selection: Selection) -> Vec<Selection> {
+    let start = selection.start.min(text.len());
+    let mut end = selection.end.min(text.len()).max(start);
+    if end > start && text[end - 1] == '\n' {
+        end -= 1;
+    }
+
+    let mut lines = Vec::new();
+    let mut line = line_bounds(text, start);
+    loop {
+        lines.push(line);
+        if line.end >= end || line.end >= text.len() {
+            break;
+        }
+        line = line_bounds(text, line.end + 1);
+    }
+    lines
+}
+
+pub fn rewrite_line_prefixes(
+    state: &mut EditorState,
+    lines: &[Selection],
+    mut rewrite: impl FnMut(&str) -> Option<(usize, String)>,
+) -> bool {
+    let mut text: Vec<char> = state.text.chars().collect();
+    let mut selection = state.char_selection();
+    let mut changed = false;
+
+    for line in lines.iter().rev() {
+        let line_text: String = text[line.start..line.end].iter().collect();
+        let Some((old_len, prefix)) = rewrite(&line_text) else {
+            continue;
+        };
+
+        let new_prefix: Vec<char> = prefix.chars().collect();
+        if text[line.start..line.start + old_len] == new_prefix[..] {
+            continue;
+        }
+
+        let new_len = new_prefix.len();
+        let shift = |pos: usize| {
+            if pos < line.start {
+                pos
+            } else if pos < line.start + old_len {
+                line.start + (pos - line.start).min(new_len)
+            } else {
+                pos - old_len + new_len
+            }
+        };
+        selection = Selection::new(shift(selection.start), shift(selection.end));
+        text.splice(line.start..line.start + old_len, new_prefix);
+        changed = true;
+    }
+
+    if changed {
+        state.text = text.into_iter().collect();
+        state.selection = selection;
+    }
+    changed
+}
+
+pub fn toggle_tasks(state: &mut EditorState) -> bool {
+    let text: Vec<char> = state.text.chars().collect();
+    let blocks = code_blocks(&text);
+    let mut lines = selected_lines(&text, state.char_selection());
+    lines.retain(|line| !in_code_block(&blocks, line.start));
+    let tasks: Vec<bool> = lines
+        .iter()
+        .filter_map(|line| {
+            let line_text: String = text[line.start..line.end].iter().collect();
+            match parse_line(&line_text).1?.marker {
+                ListMarker::Task(_, checked) => Some(checked),
+                _ => None,
+            }
+        })
+        .collect();
+    if tasks.is_empty() {
+        return false;
+    }
+
+    let checked = tasks.iter().any(|checked| !checked);
+    rewrite_line_prefixes(state, &lines, |line| {
+        let (quote, item) = parse_line(line);
+        let item = item?;
+        let ListMarker::Task(bullet, _) = item.marker else {
+            return None;
+        };
+        let box_end = quote.chars().count() + item.indent.chars().count() + 5;
+        Some((
+            box_end,
+            format!("{quote}{}{bullet} [{}]", item.indent, if checked { 'x' } else { ' ' }),
+        ))
+    })
+}
+
+pub fn toggle_task_at(state: &mut EditorState, pos: usize) -> bool {
+    let mut text: Vec<char> = state.text.chars().collect();
+    if pos >= text.len() || in_code_block(&code_blocks(&text), pos) {
+        return false;
+    }
+
+    let line = selected_lines(&text, Selection::new(pos, pos))[0];
+    let line_text: String = text[line.start..line.end].iter().collect();
+    let (quote, item) = parse_line(&line_text);
+    let Some(ListItem {
+        indent,
+        marker: ListMarker::Task(_, is_checked),
+        ..
+    }) = item
+    else {
+        return false;
+    };
+    if pos != line.start + quote.chars().count() + indent.chars().count() + 2 {
+        return false;
+    }
+
+    let checked = if is_checked { ' ' } else { 'x' };
+
+    text[pos + 1] = checked;
+    state.text = text.into_iter().collect();
+    true
+}
+
+fn in_code_block(blocks: &[CodeBlock], pos: usize) -> bool {
+    blocks
+        .iter()
+        .any(|block| block.opening.start <= pos && pos < block.end())
+}
+
+fn indent_width(indent: &str) -> usize {
+    indent.chars().map(|ch| if ch == '\t' { 4 } else { 1 }).sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListKind {
+    Bulleted,
+    Ordered,
+    Task,
+}
+
+impl ListKind {
+    pub fn matches(&self, marker: &ListMarker) -> bool {
+        matches!(
+            (self, marker),
+            (ListKind::Bulleted, ListMarker::Bullet(_))
+                | (ListKind::Ordered, ListMarker::Ordered(..))
+                | (ListKind::Task, ListMarker::Task(..))
+        )
+    }
+
+    fn marker(&self, current: Option<ListMarker>, number: u64, delimiter: char) -> ListMarker {
+        let bullet = match current {
+            Some(ListMarker::Bullet(bullet) | ListMarker::Task(bullet, _)) => bullet,
+            _ => '-',
+        };
+        match (self, current) {
+            (ListKind::Bulleted, _) => ListMarker::Bullet(bullet),
+            (ListKind::Ordered, _) => ListMarker::Ordered(number, delimiter),
+            (ListKind::Task, Some(marker @ ListMarker::Task(..))) => marker,
+            (ListKind::Task, _) => ListMarker::Task(bullet, false),
+        }
+    }
+}
+
+fn quote_prefix_len(line: &str) -> usize {
+    let mut len = 0;
+    loop {
+        let rest = &line[len..];
+        let spaces = rest.len() - rest.trim_start_matches(' ').len();
+        match rest[spaces..].strip_prefix('>') {
+            Some(content) if spaces < 4 => len += spaces + 1 + content.starts_with(' ') as usize,
+            _ => return len,
+        }
+    }
+}
+
+fn parse_line(line: &str) -> (&str, Option<ListItem>) {
+    let (quote, rest) = line.split_at(quote_prefix_len(line));
+    (quote, ListItem::parse(rest))
+}
+
+pub fn toggle_list(state: &mut EditorState, kind: ListKind) -> bool {
+    let text: Vec<char> = state.text.chars().collect();
+    let mut lines = selected_lines(&text, state.char_selection());
+    let line_text = |line: &Selection| text[line.start..line.end].iter().collect::<String>();
+    if lines.len() > 1 {
+        lines.retain(|line| !line_text(line).trim().is_empty());
+        if lines.is_empty() {
+            return false;
+        }
+    }
+
+    let sources: Vec<String> = lines.iter().map(line_text).collect();
+    let is_kind = sources
+        .iter()
+        .all(|line| parse_line(line).1.is_some_and(|item| kind.matches(&item.marker)));
+
+    let mut prefixes = Vec::with_capacity(sources.len());
+    if is_kind {
+        for line in &sources {
+            let (quote, item) = parse_line(line);
+            prefixes.push(item.map(|item| {
+                (
+                    quote.chars().count() + item.marker_len,
+                    format!("{quote}{}", item.indent),
+                )
+            }));
+        }
+    } else {
+        let previous = lines[0]
+            .start
+            .checked_sub(1)
+            .map(|end| line_text(&line_bounds(&text, end)));
+        let continued = previous
+            .as_deref()
+            .map(parse_line)
+            .and_then(|(_, item)| match item?.marker {
+                ListMarker::Ordered(number, delimiter) => Some((number + 1, delimiter)),
+                _ => None,
+            });
+        let delimiter = continued
+            .map(|(_, delimiter)| delimiter)
+            .or_else(|| {
+                sources.iter().find_map(|line| match parse_line(line).1?.marker {
+                    ListMarker::Ordered(_, delimiter) => Some(delimiter),
+                    _ => None,
+                })
+            })
+            .unwrap_or('.');
+
+        let mut levels: Vec<(usize, u64)> = Vec::new();
+        let mut parents: Vec<(usize, isize)> = Vec::new();
+        for (idx, line) in sources.iter().enumerate() {
+            let (quote, item) = parse_line(line);
+            let (old_len, indent, current) = match item {
+                Some(item) => (item.marker_len, item.indent, Some(item.marker)),
+                None => {
+                    let rest = &line[quote.len()..];
+                    let indent: String = rest.chars().take_while(|&ch| ch == ' ' || ch == '\t').collect();
+                    (indent.chars().count(), indent, None)
+                },
+            };
+
+            let old_width = indent_width(&indent);
+            while parents.last().is_some_and(|&(content, _)| content > old_width) {
+                parents.pop();
+            }
+            let shift = parents.last().map(|&(_, shift)| shift).unwrap_or(0);
+            let width = old_width.saturating_add_signed(shift);
+            let indent = if shift == 0 { indent } else { " ".repeat(width) };
+
+            while levels.last().is_some_and(|&(level, _)| level > width) {
+                levels.pop();
+            }
+            let number = match levels.last_mut() {
+                Some((level, next)) if *level == width => {
+                    *next += 1;
+                    *next - 1
+                },
+                _ => {
+                    let number = match current {
+                        Some(ListMarker::Ordered(number, _)) => number,
+                        _ if idx == 0 => continued.map(|(number, _)| number).unwrap_or(1),
+                        _ => 1,
+                    };
+                    levels.push((width, number + 1));
+                    number
+                },
+            };
+
+            let marker = kind.marker(current, number, delimiter);
+            if let Some(current) = current {
+                let old_content = old_width + current.content_offset();
+                let new_content = width + marker.content_offset();
+                parents.push((old_content, new_content as isize - old_content as isize));
+            }
+            prefixes.push(Some((
+                quote.chars().count() + old_len,
+                format!("{quote}{indent}{marker}"),
+            )));
+        }
+    }
+
+    let mut prefixes = prefixes.into_iter().rev();
+    let changed = rewrite_line_prefixes(state, &lines, |_| prefixes.next().flatten());
+    renumber_lists(state);
+    changed
+}
+
+fn is_list_line(line: &str) -> bool {
+    line.trim().is_empty()
+        || line.starts_with([' ', '\t'])
+        || ListItem::parse(line).is_some_and(|item| item.marker.is_list())
+}
+
+fn list_region(text: &[char], selection: Selection, blocks: &[CodeBlock]) -> Option<Vec<Selection>> {
+    let block_at = |line: &Selection| {
+        blocks
+            .iter()
+            .find(|block| block.opening.start <= line.start && line.end <= block.end())
+    };
+    let is_region_line = |line: &Selection| match block_at(line) {
+        Some(block) => block.opening.start < block.opening.end && text[block.opening.start] == ' ',
+        None => is_list_line(&text[line.start..line.end].iter().collect::<String>()),
+    };
+
+    let mut lines = selected_lines(text, selection);
+    if lines.iter().any(|line| block_at(line).is_some()) {
+        return None;
+    }
+
+    while let Some(previous) = lines[0].start.checked_sub(1).map(|end| line_bounds(text, end)) {
+        if !is_region_line(&previous) {
+            break;
+        }
+        lines.insert(0, previous);
+    }
+    while let Some(next) = lines
+        .last()
+        .filter(|line| line.end < text.len())
+        .map(|line| line_bounds(text, line.end + 1))
+    {
+        if !is_region_line(&next) {
+            break;
+        }
+        lines.push(next);
+    }
+
+    lines.retain(|line| block_at(line).is_none());
+    Some(lines)
+}
+
+pub fn renumber_lists(state: &mut EditorState) -> bool {
+    let text: Vec<char> = state.text.chars().collect();
+    let blocks = code_blocks(&text);
+    let Some(lines) = list_region(&text, state.char_selection().clamp(text.len()), &blocks) else {
+        return false;
+    };
+    let mut prefixes = Vec::with_capacity(lines.len());
+    let mut levels: Vec<(usize, Option<(char, u64)>)> = Vec::new();
+
+    for line in &lines {
+        let line_text: String = text[line.start..line.end].iter().collect();
+        if line_text.trim().is_empty() {
+            prefixes.push(None);
+            continue;
+        }
+
+        let item = ListItem::parse(&line_text).filter(|item| item.marker.is_list());
+        let indent = match &item {
+            Some(item) => indent_width(&item.indent),
+            None => indent_width(leading_whitespace(&line_text)),
+        };
+        while levels.last().is_some_and(|&(level, _)| level > indent) {
+            levels.pop();
+        }
+
+        let Some(item) = item else {
+            if levels.last().is_some_and(|&(level, _)| level == indent) {
+                levels.pop();
+            }
+            prefixes.push(None);
+            continue;
+        };
+
+        let ListMarker::Ordered(number, delimiter) = item.marker else {
+            if levels.last().is_some_and(|&(level, _)| level == indent) {
+                levels.pop();
+            }
+            levels.push((indent, None));
+            prefixes.push(None);
+            continue;
+        };
+
+        let expected = match levels.last_mut() {
+            Some((level, Some((level_delimiter, next)))) if *level == indent && *level_delimiter == delimiter => {
+                *next += 1;
+                *next - 1
+            },
+            _ => {
+                if levels.last().is_some_and(|&(level, _)| level == indent) {
+                    levels.pop();
+                }
+                let start = if levels.is_empty() { number } else { 1 };
+                levels.push((indent, Some((delimiter, start + 1))));
+                start
+            },
+        };
+        prefixes.push(Some((
+            item.marker_len,
+            format!("{}{}", item.indent, ListMarker::Ordered(expected, delimiter)),
+        )));
+    }
+
+    let mut prefixes = prefixes.into_iter().rev();
+    rewrite_line_prefixes(state, &lines, |_| prefixes.next().flatten())
+}
+
+fn leading_whitespace(line: &str) -> &str {
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+fn previous_item(text: &[char], line: Selection, max_indent: usize, strict: bool) -> Option<ListItem> {
+    let mut pos = line.start;
+    while pos > 0 {
+        let previous = line_bounds(text, pos - 1);
+        pos = previous.start;
+        let line_text: String = text[previous.start..previous.end].iter().collect();
+        if line_text.trim().is_empty() {
+            continue;
+        }
+
+        let indent = indent_width(leading_whitespace(&line_text));
+        if indent < max_indent || (!strict && indent == max_indent) {
+            return ListItem::parse(&line_text).filter(|item| item.marker.is_list());
+        }
+    }
+    None
+}
+
+pub fn indent_lines(state: &mut EditorState, unit: &str) -> bool {
+    let text: Vec<char> = state.text.chars().collect();
+    let selection = state.char_selection();
+    let lines = selected_lines(&text, selection);
+
+    if selection.is_empty() {
+        let line = lines[0];
+        let line_text: String = text[line.start..line.end].iter().collect();
+        if !ListItem::parse(&line_text).is_some_and(|item| item.marker.is_list()) {
+            let caret = selection.start.min(text.len());
+            let unit_len = unit.chars().count();
+            let mut text = text;
+            text.splice(caret..caret, unit.chars());
+            state.text = text.into_iter().collect();
+            state.selection = Selection::new(caret + unit_len, caret + unit_len);
+            return true;
+        }
+    }
+
+    let mut indents: Vec<Option<String>> = lines
+        .iter()
+        .map(|&line| {
+            let line_text: String = text[line.start..line.end].iter().collect();
+            if line_text.trim().is_empty() && lines.len() > 1 {
+                return None;
+            }
+
+            let indent = leading_whitespace(&line_text).to_string();
+            let nested = ListItem::parse(&line_text)
+                .filter(|item| item.marker.is_list())
+                .and_then(|_| previous_item(&text, line, indent_width(&indent), false))
+                .filter(|sibling| indent_width(&sibling.indent) == indent_width(&indent))
+                .map(|sibling| " ".repeat(sibling.marker.content_offset()));
+            Some(format!("{indent}{}", nested.unwrap_or_else(|| unit.to_string())))
+        })
+        .collect();
+
+    rewrite_line_prefixes(state, &lines, |line| {
+        let indent = indents.pop().flatten()?;
+        Some((leading_whitespace(line).chars().count(), indent))
+    })
+}
+
+pub fn outdent_lines(state: &mut EditorState, unit: &str) -> bool {
+    let text: Vec<char> = state.text.chars().collect();
+    let lines = selected_lines(&text, state.char_selection());
+    let unit_width = indent_width(unit).max(1);
+
+    let mut indents: Vec<Option<String>> = lines
+        .iter()
+        .map(|&line| {
+            let line_text: String = text[line.start..line.end].iter().collect();
+            let indent = leading_whitespace(&line_text);
+            if indent.is_empty() {
+                return None;
+            }
+
+            let parent = ListItem::parse(&line_text)
+                .filter(|item| item.marker.is_list())
+                .and_then(|_| previous_item(&text, line, indent_width(indent), true));
+            Some(match parent {
+                Some(parent) => parent.indent,
+                None if indent.ends_with('\t') => indent[..indent.len() - 1].to_string(),
+                None => {
+                    let spaces = indent.chars().rev().take_while(|&ch| ch == ' ').count();
+                    indent[..indent.len() - spaces.min(unit_width)].to_string()
+                },
+            })
+        })
+        .collect();
+
+    rewrite_line_prefixes(state, &lines, |line| {
+        let indent = indents.pop().flatten()?;
+        Some((leading_whitespace(line).chars().count(), indent))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Range;
+
+    use super::*;
+
+    fn state(text: &str) -> EditorState {
+        EditorState::new(text, 0..text.chars().count())
+    }
+
+    fn toggled(text: &str, kind: ListKind) -> String {
+        let mut state = state(text);
+        toggle_list(&mut state, kind);
+        state.text
+    }
+
+    #[test]
+    fn toggle_list_keeps_flat_lines_flat() {
+        assert_eq!(toggled("a\nb\nc", ListKind::Bulleted), "- a\n- b\n- c");
+        assert_eq!(toggled("a\nb\nc", ListKind::Ordered), "1. a\n2. b\n3. c");
+        assert_eq!(toggled("a\nb", ListKind::Task), "- [ ] a\n- [ ] b");
+    }
+
+    #[test]
+    fn toggle_list_skips_blank_lines() {
+        assert_eq!(toggled("a\n\nb", ListKind::Bulleted), "- a\n\n- b");
+        assert_eq!(toggled("a\n\nb", ListKind::Ordered), "1. a\n\n2. b");
+    }
+
+    #[test]
+    fn toggle_list_converts_markers_in_place() {
+        assert_eq!(toggled("- a\n- b", ListKind::Ordered), "1. a\n2. b");
+        assert_eq!(toggled("1. a\n2. b", ListKind::Task), "- [ ] a\n- [ ] b");
+        assert_eq!(toggled("- [x] a\n* b", ListKind::Task), "- [x] a\n* [ ] b");
+        assert_eq!(toggled("> a\n> b", ListKind::Bulleted), "> - a\n> - b");
+    }
+
+    #[test]
+    fn toggle_list_removes_markers_of_the_same_kind() {
+        assert_eq!(toggled("- a\n  - b", ListKind::Bulleted), "a\n  b");
+        assert_eq!(toggled("- [ ] a\n- [x] b", ListKind::Task), "a\nb");
+    }
+
+    #[test]
+    fn toggle_list_keeps_nesting() {
+        assert_eq!(
+            toggled("- a\n  - b\n    - c\n- d", ListKind::Ordered),
+            "1. a\n   1. b\n      1. c\n2. d"
+        );
+        assert_eq!(toggled("1. a\n   1. b\n2. c", ListKind::Bulleted), "- a\n  - b\n- c");
+        assert_eq!(
+            toggled("1. a\n   - b\n2. c", ListKind::Task),
+            "- [ ] a\n  - [ ] b\n- [ ] c"
+        );
+    }
+
+    fn continued(text: &str) -> Option<String> {
+        let caret = text.chars().count();
+        let mut state = EditorState::new(text, caret..caret);
+        continue_list(&mut state).then_some(state.text)
+    }
+
+    #[test]
+    fn continue_list_starts_next_item() {
+        assert_eq!(continued("- a").as_deref(), Some("- a\n- "));
+        assert_eq!(continued("  1. a").as_deref(), Some("  1. a\n  2. "));
+        assert_eq!(continued("- [x] a").as_deref(), Some("- [x] a\n- [ ] "));
+        assert_eq!(continued("> a").as_deref(), Some("> a\n> "));
+        assert_eq!(continued("a\n- ").as_deref(), Some("a\n"));
+        assert_eq!(continued("a"), None);
+    }
+
+    #[test]
+    fn continue_list_ignores_thematic_breaks() {
+        assert_eq!(continued("* * *"), None);
+        assert_eq!(continued("- - -"), None);
+        assert_eq!(continued("___"), None);
+        assert!(ListItem::parse("* * *").is_none());
+        assert!(ListItem::parse("* *").is_some());
+    }
+
+    #[test]
+    fn continue_list_ignores_code_blocks() {
+        assert_eq!(continued("```\n- a"), None);
+        assert_eq!(continued("~~~md\n1. a"), None);
+        assert_eq!(continued("```\n```\n- a").as_deref(), Some("```\n```\n- a\n- "));
+    }
+
+    fn renumbered(text: &str, caret: usize) -> String {
+        let mut state = EditorState::new(text, caret..caret);
+        renumber_lists(&mut state);
+        state.text
+    }
+
+    #[test]
+    fn renumber_lists_numbers_items_sequentially() {
+        assert_eq!(renumbered("1. a\n1. b\n1. c", 0), "1. a\n2. b\n3. c");
+        assert_eq!(renumbered("3. a\n1. b", 0), "3. a\n4. b");
+        assert_eq!(renumbered("1. a\n\n1. b", 0), "1. a\n\n2. b");
+        assert_eq!(renumbered("1) a\n1. b", 0), "1) a\n1. b");
+    }
+
+    #[test]
+    fn renumber_lists_restarts_nested_lists() {
+        assert_eq!(
+            renumbered("1. a\n   2. b\n   3. c\n2. d", 0),
+            "1. a\n   1. b\n   2. c\n2. d"
+        );
+        assert_eq!(renumbered("- a\n  2. b\n  5. c", 0), "- a\n  1. b\n  2. c");
+    }
+
+    #[test]
+    fn renumber_lists_skips_code_blocks() {
+        let code = "```\n1. x\n1. y\n```";
+        assert_eq!(renumbered(code, 6), code);
+        assert_eq!(renumbered(code, code.len()), code);
+
+        let text = format!("1. a\n1. b\n{code}");
+        assert_eq!(renumbered(&text, 0), format!("1. a\n2. b\n{code}"));
+    }
+
+    #[test]
+    fn renumber_lists_only_touches_the_edited_list() {
+        let text = "1. a\n1. b\n\nfoo\n\n1. x\n1. y";
+        assert_eq!(renumbered(text, 0), "1. a\n2. b\n\nfoo\n\n1. x\n1. y");
+        assert_eq!(renumbered(text, text.len()), "1. a\n1. b\n\nfoo\n\n1. x\n2. y");
+    }
+
+    fn indented(text: &str, selection: Range<usize>) -> String {
+        let mut state = EditorState::new(text, selection);
+        indent_lines(&mut state, "    ");
+        state.text
+    }
+
+    fn outdented(text: &str, selection: Range<usize>) -> String {
+        let mut state = EditorState::new(text, selection);
+        outdent_lines(&mut state, "    ");
+        state.text
+    }
+
+    #[test]
+    fn indent_lines_nests_under_previous_item() {
+        assert_eq!(indented("- a\n- b", 6..6), "- a\n  - b");
+        assert_eq!(indented("1. a\n2. b", 8..8), "1. a\n   2. b");
+        assert_eq!(indented("- [ ] a\n- [ ] b", 12..12), "- [ ] a\n  - [ ] b");
+        assert_eq!(
+            indented("- [x] a\n  - [ ] b\n  - [ ] c", 24..24),
+            "- [x] a\n  - [ ] b\n    - [ ] c"
+        );
+    }
+
+    #[test]
+    fn indent_lines_inserts_unit_outside_lists() {
+        assert_eq!(indented("text", 2..2), "te    xt");
+        assert_eq!(indented("a\nb", 0..3), "    a\n    b");
+    }
+
+    #[test]
+    fn outdent_lines_moves_items_to_parent_level() {
+        assert_eq!(outdented("- [ ] a\n  - [ ] b", 12..12), "- [ ] a\n- [ ] b");
+        assert_eq!(outdented("1. a\n   1. b", 10..10), "1. a\n1. b");
+        assert_eq!(outdented("      a", 0..0), "  a");
+    }
+
+    #[test]
+    fn toggle_list_inserts_marker_at_caret() {
+        let mut state = EditorState::new("text", 2..2);
+        toggle_list(&mut state, ListKind::Bulleted);
+        assert_eq!(state, EditorState::new("- text", 4..4));
+    }
+
+    #[test]
+    fn toggle_tasks_handles_quoted_items() {
+        let mut state = EditorState::new("> - [ ] a", 0..0);
+        assert!(toggle_tasks(&mut state));
+        assert_eq!(state.text, "> - [x] a");
+    }
+
+    #[test]
+    fn toggle_tasks_skips_code_blocks() {
+        let text = "```\n- [ ] a\n```";
+        let mut state = EditorState::new(text, 0..text.chars().count());
+        assert!(!toggle_tasks(&mut state));
+        assert_eq!(state.text, text);
+    }
+
+    #[test]
+    fn toggle_task_at_flips_the_task_box() {
+        let mut state = EditorState::new("- [ ] a\n> * [x] b", 0..0);
+        assert!(toggle_task_at(&mut state, 2));
+        assert!(toggle_task_at(&mut state, 12));
+        assert_eq!(state.text, "- [x] a\n> * [ ] b");
+    }
+
+    #[test]
+    fn toggle_task_at_rejects_boxes_outside_task_items() {
+        let text = "a [ ] b\n- [ ] c [ ]\n```\n- [ ] d\n```";
+        let mut state = EditorState::new(text, 0..0);
+        for pos in [2, 16, 24, 100] {
+            assert!(!toggle_task_at(&mut state, pos), "{pos}");
+        }
+        assert_eq!(state.text, text);
+    }
+}
@@ -0,0 +1,762 @@
+use std::ops::Range;
+
+use web_sys::{HtmlInputElement, HtmlTextAreaElement};
+use yew::{
+    html, Callback, Component, Context, Html, InputEvent, KeyboardEvent, MouseEvent, Properties, SubmitEvent,
+    TargetCast,
+};
+
+use super::list::line_bounds;
+use super::tool::{BuiltinAction, EditorAction};
+use super::{edit_textarea, find_textarea, EditorContext, EditorState, Selection};
+use crate::Widget;
+
+pub const DEFAULT_TABLE_ROWS: usize = 2;
+pub const DEFAULT_TABLE_COLUMNS: usize = 3;
+
+const MIN_COLUMN_WIDTH: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    #[default]
+    None,
+    Left,
+    Center,
+    Right,
+}
+
+impl Alignment {
+    pub fn parse(cell: &str) -> Option<Self> {
+        let cell = cell.trim();
+        let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+        if dashes.is_empty() || !dashes.chars().all(|ch| ch == '-') {
+            return None;
+        }
+
+        Some(match (cell.starts_with(':'), cell.len() > 1 && cell.ends_with(':')) {
+            (true, true) => Self::Center,
+            (true, false) => Self::Left,
+            (false, true) => Self::Right,
+            (false, false) => Self::None,
+        })
+    }
+
+    pub fn delimiter(&self, width: usize) -> String {
+        let width = width.max(MIN_COLUMN_WIDTH);
+        match self {
+            Self::None => "-".repeat(width),
+            Self::Left => format!(":{}", "-".repeat(width - 1)),
+            Self::Center => format!(":{}:", "-".repeat(width - 2)),
+            Self::Right => format!("{}:", "-".repeat(width - 1)),
+        }
+    }
+
+    fn padding(&self, space: usize) -> (usize, usize) {
+        match self {
+            Self::None | Self::Left => (0, space),
+            Self::Center => (space / 2, space - space / 2),
+            Self::Right => (space, 0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub rows: Vec<Vec<String>>,
+    pub alignments: Vec<Alignment>,
+}
+
+impl Table {
+    pub fn new(rows: usize, columns: usize) -> Self {
+        let columns = columns.max(1);
+        let header = (1..=columns).map(|column| format!("Column {column}")).collect();
+        Self {
+            rows: std::iter::once(header)
+                .chain((0..rows).map(|_| vec![String::new(); columns]))
+                .collect(),
+            alignments: vec![Alignment::None; columns],
+        }
+    }
+
+    pub fn parse(lines: &[String]) -> Option<Self> {
+        let (header, rest) = lines.split_first()?;
+        let (delimiter, body) = rest.split_first()?;
+        let alignments = split_cells(delimiter)
+            .into_iter()
+            .map(|(cell, _)| Alignment::parse(&cell))
+            .collect::<Option<Vec<_>>>()?;
+        if alignments.is_empty() || !is_row(header) {
+            return None;
+        }
+
+        let mut table = Self {
+            rows: std::iter::once(header)
+                .chain(body)
+                .map(|line| split_cells(line).into_iter().map(|(cell, _)| cell).collect())
+                .collect(),
+            alignments,
+        };
+        table.normalize();
+        Some(table)
+    }
+
+    pub fn columns(&self) -> usize {
+        self.alignments.len()
+    }
+
+    pub fn normalize(&mut self) {
+        let columns = self
+            .rows
+            .iter()
+            .map(Vec::len)
+            .chain(std::iter::once(self.alignments.len()))
+            .max()
+            .unwrap_or(0)
+            .max(1);
+        self.alignments.resize(columns, Alignment::None);
+        for row in &mut self.rows {
+            row.resize(columns, String::new());
+        }
+    }
+
+    pub fn format(&self) -> String {
+        self.format_lines().0.join("\n")
+    }
+
+    fn widths(&self) -> Vec<usize> {
+        (0..self.columns())
+            .map(|column| {
+                self.rows
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    .max(MIN_COLUMN_WIDTH)
+            })
+            .collect()
+    }
+
+    fn format_lines(&self) -> (Vec<String>, Vec<Vec<usize>>) {
+        let widths = self.widths();
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        let mut cell_starts = Vec::with_capacity(self.rows.len());
+
+        for (index, row) in self.rows.iter().enumerate() {
+            let mut line = String::from("|");
+            let mut starts = Vec::with_capacity(row.len());
+            for ((cell, width), alignment) in row.iter().zip(&widths).zip(&self.alignments) {
+                let (left, right) = alignment.padding(width - cell.chars().count());
+                line.push(' ');
+                line.push_str(&" ".repeat(left));
+                starts.push(line.chars().count());
+                line.push_str(cell);
+                line.push_str(&" ".repeat(right));
+                line.push_str(" |");
+            }
+            lines.push(line);
+            cell_starts.push(starts);
+
+            if index == 0 {
+                let delimiters: Vec<String> = self
+                    .alignments
+                    .iter()
+                    .zip(&widths)
+                    .map(|(alignment, &width)| alignment.delimiter(width))
+                    .collect();
+                lines.push(format!("| {} |", delimiters.join(" | ")));
+            }
+        }
+        (lines, cell_starts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableCursor {
+    pub table: Table,
+    pub range: Selection,
+    pub row: usize,
+    pub column: usize,
+    pub offset: usize,
+}
+
+pub fn current_table(state: &EditorState) -> Option<TableCursor> {
+    let text: Vec<char> = state.text.chars().collect();
+    let caret = state.char_selection().start.min(text.len());
+    let caret_line = line_bounds(&text, caret);
+    let line_text = |line: Selection| text[line.start..line.end].iter().collect::<String>();
+    if !is_row(&line_text(caret_line)) {
+        return None;
+    }
+
+    let mut lines = vec![caret_line];
+    while let Some(first) = lines.first().copied().filter(|line| line.start > 0) {
+        let previous = line_bounds(&text, first.start - 1);
+        if !is_row(&line_text(previous)) {
+            break;
+        }
+        lines.insert(0, previous);
+    }
+    while let Some(last) = lines.last().copied().filter(|line| line.end < text.len()) {
+        let next = line_bounds(&text, last.end + 1);
+        if !is_row(&line_text(next)) {
+            break;
+        }
+        lines.push(next);
+    }
+
+    let line_index = lines.iter().position(|&line| line == caret_line)?;
+    let line_texts: Vec<String> = lines.iter().map(|&line| line_text(line)).collect();
+    let table = Table::parse(&line_texts)?;
+
+    let row = line_index.saturating_sub(1);
+    let cells = split_cells(&line_texts[line_index]);
+    let caret_offset = caret - caret_line.start;
+    let line_chars = &text[caret_line.start..caret_line.end];
+    let pipes = pipe_positions(line_chars);
+    let leading_pipe = pipes.first() == Some(&line_chars.iter().take_while(|ch| ch.is_whitespace()).count());
+    let column = (pipes.iter().filter(|&&pipe| pipe < caret_offset).count())
+        .saturating_sub(usize::from(leading_pipe))
+        .min(table.columns() - 1);
+    let offset = if line_index == 1 {
+        0
+    } else {
+        cells
+            .get(column)
+            .map(|(_, range)| caret_offset.clamp(range.start, range.end) - range.start)
+            .unwrap_or(0)
+    };
+
+    Some(TableCursor {
+        table,
+        range: Selection::from(lines[0].start..lines[lines.len() - 1].end),
+        row,
+        column,
+        offset,
+    })
+}
+
+pub fn insert_table(state: &mut EditorState, rows: usize, columns: usize) {
+    let table = Table::new(rows, columns);
+    let (lines, cell_starts) = table.format_lines();
+    let chars: Vec<char> = state.text.chars().collect();
+    let selection = state.char_selection().clamp(chars.len());
+    let before = &chars[..selection.start];
+    let after = &chars[selection.end..];
+
+    let prefix = match before {
+        [] | [.., '\n', '\n'] => "",
+        [.., '\n'] => "\n",
+        _ => "\n\n",
+    };
+    let suffix = match after {
+        [] | ['\n', '\n', ..] => "",
+        ['\n', ..] => "\n",
+        _ => "\n\n",
+    };
+
+    let header_start = selection.start + prefix.len() + cell_starts[0][0];
+    let header_len = table.rows[0][0].chars().count();
+    state.insert_text(&format!("{prefix}{}{suffix}", lines.join("\n")));
+    state.selection = Selection::from(header_start..header_start + header_len);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableCommand {
+    InsertRowAbove,
+    InsertRowBelow,
+    DeleteRow,
+    InsertColumnLeft,
+    InsertColumnRight,
+    DeleteColumn,
+    Align(Alignment),
+    Format,
+}
+
+impl TableCommand {
+    pub const ALL: [Self; 10] = [
+        Self::InsertRowAbove,
+        Self::InsertRowBelow,
+        Self::DeleteRow,
+        Self::InsertColumnLeft,
+        Self::InsertColumnRight,
+        Self::DeleteColumn,
+        Self::Align(Alignment::Left),
+        Self::Align(Alignment::Center),
+        Self::Align(Alignment::Right),
+        Self::Format,
+    ];
+}
+
+pub fn edit_table(state: &mut EditorState, command: TableCommand) -> bool {
+    let Some(TableCursor {
+        mut table,
+        range,
+        mut row,
+        mut column,
+        offset,
+    }) = current_table(state)
+    else {
+        return false;
+    };
+
+    let columns = table.columns();
+    match command {
+        TableCommand::InsertRowAbove => {
+            row = row.max(1);
+            table.rows.insert(row, vec![String::new(); columns]);
+        },
+        TableCommand::InsertRowBelow => {
+            row += 1;
+            table.rows.insert(row, vec![String::new(); columns]);
+        },
+        TableCommand::DeleteRow => {
+            if row == 0 {
+                return false;
+            }
+            table.rows.remove(row);
+            row = row.min(table.rows.len() - 1);
+        },
+        TableCommand::InsertColumnLeft | TableCommand::InsertColumnRight => {
+            if command == TableCommand::InsertColumnRight {
+                column += 1;
+            }
+            table.alignments.insert(column, Alignment::None);
+            for cells in &mut table.rows {
+                cells.insert(column, String::new());
+            }
+        },
+        TableCommand::DeleteColumn => {
+            if columns == 1 {
+                return false;
+            }
+            table.alignments.remove(column);
+            for cells in &mut table.rows {
+                cells.remove(column);
+            }
+            column = column.min(columns - 2);
+        },
+        TableCommand::Align(alignment) => table.alignments[column] = alignment,
+        TableCommand::Format => (),
+    }
+
+    let (lines, cell_starts) = table.format_lines();
+    let line_start: usize = lines
+        .iter()
+        .take(if row == 0 { 0 } else { row + 1 })
+        .map(|line| line.chars().count() + 1)
+        .sum();
+    let cell_len = table.rows[row][column].chars().count();
+    let caret = range.start + line_start + cell_starts[row][column] + offset.min(cell_len);
+
+    let mut chars: Vec<char> = state.text.chars().collect();
+    chars.splice(range.start..range.end, lines.join("\n").chars());
+    state.text = chars.into_iter().collect();
+    state.selection = Selection::from(caret..caret);
+    true
+}
+
+fn is_row(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('|') || split_cells(line).len() > 1
+}
+
+fn pipe_positions(chars: &[char]) -> Vec<usize> {
+    let mut pipes = Vec::new();
+    let mut escaped = false;
+    for (index, &ch) in chars.iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if ch == '\\' {
+            escaped = true;
+        } else if ch == '|' {
+            pipes.push(index);
+        }
+    }
+    pipes
+}
+
+fn split_cells(line: &str) -> Vec<(String, Range<usize>)> {
+    let chars: Vec<char> = line.chars().collect();
+    let content_start = chars.iter().take_while(|ch| ch.is_whitespace()).count();
+    let content_end = chars.len() - chars.iter().rev().take_while(|ch| ch.is_whitespace()).count();
+
+    let pipes = pipe_positions(&chars);
+    let mut segments = Vec::with_capacity(pipes.len() + 1);
+    let mut start = content_start;
+    for &pipe in &pipes {
+        segments.push(start..pipe);
+        start = pipe + 1;
+    }
+    segments.push(start..content_end.max(start));
+    if pipes.first() == Some(&content_start) {
+        segments.remove(0);
+    }
+    if !segments.is_empty() && pipes.last().is_some_and(|&pipe| pipe + 1 == content_end) {
+        segments.pop();
+    }
+
+    segments
+        .into_iter()
+        .map(|segment| {
+            let cell = &chars[segment.clone()];
+            let start = segment.start + cell.iter().take_while(|ch| ch.is_whitespace()).count();
+            let end = segment.end - cell.iter().rev().take_while(|ch| ch.is_whitespace()).count();
+            let end = end.max(start);
+            (chars[start..end].iter().collect(), start..end)
+        })
+        .collect()
+}
+
+impl EditorAction for TableCommand {
+    fn id(&self) -> &str {
+        match self {
+            Self::InsertRowAbove => "table-insert-row-above",
+            Self::InsertRowBelow => "table-insert-row-below",
+            Self::DeleteRow => "table-delete-row",
+            Self::InsertColumnLeft => "table-insert-column-left",
+            Self::InsertColumnRight => "table-insert-column-right",
+            Self::DeleteColumn => "table-delete-column",
+            Self::Align(Alignment::None) => "table-align-none",
+            Self::Align(Alignment::Left) => "table-align-left",
+            Self::Align(Alignment::Center) => "table-align-center",
+            Self::Align(Alignment::Right) => "table-align-right",
+            Self::Format => "table-format",
+        }
+    }
+
+    fn title(&self) -> &str {
+        match self {
+            Self::InsertRowAbove => "Insert row above",
+            Self::InsertRowBelow => "Insert row below",
+            Self::DeleteRow => "Delete row",
+            Self::InsertColumnLeft => "Insert column left",
+            Self::InsertColumnRight => "Insert column right",
+            Self::DeleteColumn => "Delete column",
+            Self::Align(Alignment::None) => "Reset alignment",
+            Self::Align(Alignment::Left) => "Align left",
+            Self::Align(Alignment::Center) => "Align center",
+            Self::Align(Alignment::Right) => "Align right",
+            Self::Format => "Format table",
+        }
+    }
+
+    fn icon(&self, _size: u32) -> Html {
+        html! { { self.title() } }
+    }
+
+    fn apply(&self, state: &mut EditorState) {
+        edit_table(state, *self);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TableTool {
+    pub textarea_selector: String,
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub rows: usize,
+    pub columns: usize,
+}
+
+impl Default for TableTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TableTool {
+    pub fn new() -> Self {
+        let action = BuiltinAction::Table(DEFAULT_TABLE_ROWS, DEFAULT_TABLE_COLUMNS);
+        Self {
+            textarea_selector: ".lew-simple__textarea".to_string(),
+            class: "lew-simple__tool_button".to_string(),
+            title: action.title().to_string(),
+            size: 16,
+            rows: DEFAULT_TABLE_ROWS,
+            columns: DEFAULT_TABLE_COLUMNS,
+        }
+    }
+
+    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
+        self.textarea_selector = selector.into();
+        self
+    }
+
+    pub fn with_class(mut self, class: impl Into<String>) -> Self {
+        self.class = class.into();
+        self
+    }
+
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    pub fn with_dimensions(mut self, rows: usize, columns: usize) -> Self {
+        self.rows = rows;
+        self.columns = columns;
+        self
+    }
+}
+
+impl Widget for TableTool {
+    fn build(&self) -> Html {
+        html! {
+            <TablePopover class = { self.class.clone() } title = { self.title.clone() } size = { self.size }
+                    textarea_selector = { self.textarea_selector.clone() } rows = { self.rows } columns = { self.columns } />
+        }
+    }
+}
+
+pub enum TablePopoverMsg {
+    Open,
+    Close,
+    SetRows(String),
+    SetColumns(String),
+    Insert,
+    Command(TableCommand),
+}
+
+pub struct TablePopover {
+    is_open: bool,
+    in_table: bool,
+    rows: usize,
+    columns: usize,
+}
+
+#[derive(Clone, Properties, PartialEq)]
+pub struct TablePopoverProps {
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub textarea_selector: String,
+
+    #[prop_or(DEFAULT_TABLE_ROWS)]
+    pub rows: usize,
+
+    #[prop_or(DEFAULT_TABLE_COLUMNS)]
+    pub columns: usize,
+}
+
+impl TablePopover {
+    fn textarea(ctx: &Context<Self>) -> Option<HtmlTextAreaElement> {
+        ctx.link()
+            .context::<EditorContext>(Callback::noop())
+            .and_then(|(context, _)| context.textarea())
+            .or_else(|| find_textarea(&ctx.props().textarea_selector))
+    }
+
+    fn view_popover(&self, ctx: &Context<Self>) -> Html {
+        let onsubmit = ctx.link().callback(|event: SubmitEvent| {
+            event.prevent_default();
+            TablePopoverMsg::Insert
+        });
+        let onkeydown = ctx.link().batch_callback(|event: KeyboardEvent| {
+            (event.key() == "Escape").then(|| {
+                event.prevent_default();
+                TablePopoverMsg::Close
+            })
+        });
+        let input_value = |event: InputEvent| event.target_unchecked_into::<HtmlInputElement>().value();
+
+        html! {
+            <form class = "lew-simple__popover" { onsubmit } { onkeydown }>
+                <label class = "lew-simple__popover_field">
+                    { "Rows" }
+                    <input type = "number" min = "0" value = { self.rows.to_string() }
+                            oninput = { ctx.link().callback(move |event| TablePopoverMsg::SetRows(input_value(event))) } />
+                </label>
+                <label class = "lew-simple__popover_field">
+                    { "Columns" }
+                    <input type = "number" min = "1" value = { self.columns.to_string() }
+                            oninput = { ctx.link().callback(move |event| TablePopoverMsg::SetColumns(input_value(event))) } />
+                </label>
+                <div class = "lew-simple__popover_actions">
+                    <button type = "submit">{ "Insert" }</button>
+                    <button type = "button" onclick = { ctx.link().callback(|_: MouseEvent| TablePopoverMsg::Close) }>
+                        { "Cancel" }
+                    </button>
+                </div>
+                if self.in_table {
+                    <div class = "lew-simple__popover_commands">
+                        {
+                            TableCommand::ALL
+                                .into_iter()
+                                .map(|command| html! {
+                                    <button type = "button" title = { command.title().to_string() }
+                                            onclick = { ctx.link().callback(move |_: MouseEvent| TablePopoverMsg::Command(command)) }>
+                                        { command.icon(ctx.props().size) }
+                                    </button>
+                                })
+                                .collect::<Html>()
+                        }
+                    </div>
+                }
+            </form>
+        }
+    }
+}
+
+impl Component for TablePopover {
+    type Message = TablePopoverMsg;
+    type Properties = TablePopoverProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self {
+            is_open: false,
+            in_table: false,
+            rows: ctx.props().rows,
+            columns: ctx.props().columns,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            TablePopoverMsg::Open => {
+                self.is_open = true;
+                self.in_table = Self::textarea(ctx)
+                    .is_some_and(|textarea| current_table(&EditorState::from_textarea(&textarea)).is_some());
+            },
+            TablePopoverMsg::Close => self.is_open = false,
+            TablePopoverMsg::SetRows(rows) => {
+                if let Ok(rows) = rows.trim().parse() {
+                    self.rows = rows;
+                }
+            },
+            TablePopoverMsg::SetColumns(columns) => {
+                if let Ok(columns) = columns.trim().parse::<usize>() {
+                    self.columns = columns.max(1);
+                }
+            },
+            TablePopoverMsg::Insert => {
+                if let Some(textarea) = Self::textarea(ctx) {
+                    BuiltinAction::Table(self.rows, self.columns).run(&textarea);
+                }
+                self.is_open = false;
+            },
+            TablePopoverMsg::Command(command) => {
+                if let Some(textarea) = Self::textarea(ctx) {
+                    edit_textarea(&textarea, |state| {
+                        edit_table(state, command);
+                    });
+                }
+            },
+        }
+        true
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.rows = ctx.props().rows;
+        self.columns = ctx.props().columns;
+        true
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let props = ctx.props();
+        let onclick = ctx.link().callback({
+            let is_open = self.is_open;
+            move |_: MouseEvent| {
+                if is_open {
+                    TablePopoverMsg::Close
+                } else {
+                    TablePopoverMsg::Open
+                }
+            }
+        });
+
+        html! {
+            <span class = "lew-simple__popover_anchor">
+                <button class = { props.class.clone() } style = { format!("width: {0}; height: {0}", props.size) }
+                        type = "button" title = { props.title.clone() } { onclick }>
+                    { BuiltinAction::Table(props.rows, props.columns).icon(props.size) }
+                </button>
+                if self.is_open {
+                    { self.view_popover(ctx) }
+                }
+            </span>
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLE: &str = "| a | b |\n|---|:-:|\n| 1 | 2 |";
+
+    fn edited(text: &str, caret: usize, command: TableCommand) -> String {
+        let mut state = EditorState::new(text, caret..caret);
+        assert!(edit_table(&mut state, command));
+        state.text
+    }
+
+    #[test]
+    fn parse_reads_cells_and_alignments() {
+        let lines: Vec<String> = TABLE.lines().map(String::from).collect();
+        let table = Table::parse(&lines).unwrap();
+        assert_eq!(table.rows, vec![vec!["a", "b"], vec!["1", "2"]]);
+        assert_eq!(table.alignments, vec![Alignment::None, Alignment::Center]);
+        assert!(Table::parse(&["| a |".to_string(), "| b |".to_string()]).is_none());
+    }
+
+    #[test]
+    fn format_pads_cells_to_column_width() {
+        let lines: Vec<String> = TABLE.lines().map(String::from).collect();
+        assert_eq!(
+            Table::parse(&lines).unwrap().format(),
+            "| a   |  b  |\n| --- | :-: |\n| 1   |  2  |"
+        );
+    }
+
+    #[test]
+    fn insert_table_selects_first_header_cell() {
+        let mut state = EditorState::new("text", 4..4);
+        insert_table(&mut state, 1, 2);
+        assert_eq!(
+            state.text,
+            "text\n\n| Column 1 | Column 2 |\n| -------- | -------- |\n|          |          |"
+        );
+        assert_eq!(state.selected_text(), "Column 1");
+    }
+
+    #[test]
+    fn current_table_finds_row_and_column() {
+        let cursor = current_table(&EditorState::new(TABLE, 26..26)).unwrap();
+        assert_eq!((cursor.row, cursor.column), (1, 1));
+        assert!(current_table(&EditorState::new("text", 0..0)).is_none());
+    }
+
+    #[test]
+    fn edit_table_inserts_and_deletes_rows_and_columns() {
+        assert_eq!(
+            edited(TABLE, 22, TableCommand::InsertRowBelow),
+            "| a   |  b  |\n| --- | :-: |\n| 1   |  2  |\n|     |     |"
+        );
+        assert_eq!(
+            edited(TABLE, 22, TableCommand::DeleteRow),
+            "| a   |  b  |\n| --- | :-: |"
+        );
+        assert_eq!(
+            edited(TABLE, 2, TableCommand::InsertColumnRight),
+            "| a   |     |  b  |\n| --- | --- | :-: |\n| 1   |     |  2  |"
+        );
+        assert_eq!(
+            edited(TABLE, 2, TableCommand::DeleteColumn),
+            "|  b  |\n| :-: |\n|  2  |"
+        );
+    }
+
+    #[test]
+    fn edit_table_aligns_current_column() {
+        assert_eq!(
+            edited(TABLE, 2, TableCommand::Align(Alignment::Right)),
+            "|   a |  b  |\n| --: | :-: |\n|   1 |  2  |"
+        );
+    }
+}
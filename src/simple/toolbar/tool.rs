@@ -1,672 +1,553 @@
-use derive_more::{Deref, DerefMut};
-use yew::{html, Callback, Html, MouseEvent};
-
-use super::{replace_selected_in_textarea, textarea_selection, ReplaceFmt, UnselectedApplyMode};
+use std::fmt;
+use std::rc::Rc;
+
+use web_sys::{EventTarget, HtmlTextAreaElement, KeyboardEventInit};
+use yew::{function_component, html, use_context, Callback, Html, KeyboardEvent, MouseEvent, Properties};
+
+use super::code;
+use super::heading::{self, HeadingStyle};
+use super::list::ListKind;
+use super::table;
+use super::{edit_textarea, find_textarea, list, EditorContext, EditorState, ReplaceFmt, UnselectedApplyMode};
+use crate::keymap::{Action, Shortcut};
 use crate::Widget;
 
-#[derive(Debug, Clone, Default)]
-pub struct Tool {
-    pub textarea_selector: String,
-    pub class: String,
-    pub title: String,
-    pub size: u32,
-    pub mode: UnselectedApplyMode,
-}
+pub trait EditorAction {
+    fn id(&self) -> &str;
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Header(pub Tool);
+    fn title(&self) -> &str;
 
-impl Header {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Header".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::Line,
-        })
-    }
+    fn icon(&self, size: u32) -> Html;
 
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
+    fn apply(&self, state: &mut EditorState);
 
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
+    fn shortcut(&self) -> Option<Shortcut> {
+        None
     }
 
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
+    fn binds_shortcut(&self) -> bool {
+        true
     }
 
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
+    fn run(&self, textarea: &HtmlTextAreaElement) {
+        edit_textarea(textarea, |state| self.apply(state));
     }
+}
 
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M3.75 2a.75.75 0 01.75.75V7h7V2.75a.75.75 0 011.5 0v10.5a.75.75 0 \
-                        01-1.5 0V8.5h-7v4.75a.75.75 0 01-1.5 0V2.75A.75.75 0 013.75 2z">
-                </path>
-            </svg>
-        }
-    }
+#[derive(Clone)]
+pub struct Tool {
+    pub action: Rc<dyn EditorAction>,
+    pub textarea_selector: String,
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub shortcut: Option<Shortcut>,
 }
 
-impl Widget for Header {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(textarea_selection(&selector), ("### ", ""), mode)
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
+impl fmt::Debug for Tool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tool")
+            .field("action", &self.action.id())
+            .field("textarea_selector", &self.textarea_selector)
+            .field("class", &self.class)
+            .field("title", &self.title)
+            .field("size", &self.size)
+            .field("shortcut", &self.shortcut)
+            .finish()
     }
 }
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Bold(pub Tool);
-
-impl Bold {
-    pub fn new() -> Self {
-        Self(Tool {
+impl Tool {
+    pub fn new(action: impl EditorAction + 'static) -> Self {
+        Self {
             textarea_selector: ".lew-simple__textarea".to_string(),
             class: "lew-simple__tool_button".to_string(),
-            title: "Bold".to_string(),
+            title: action.title().to_string(),
             size: 16,
-            mode: UnselectedApplyMode::Word,
-        })
+            shortcut: action.shortcut(),
+            action: Rc::new(action),
+        }
     }
 
     pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
+        self.textarea_selector = selector.into();
         self
     }
 
     pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
+        self.class = class.into();
         self
     }
 
     pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
+        self.title = title.into();
         self
     }
 
     pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
-
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M4 2a1 1 0 00-1 1v10a1 1 0 001 1h5.5a3.5 3.5 0 001.852-6.47A3.5 3.5 0 \
-                        008.5 2H4zm4.5 5a1.5 1.5 0 100-3H5v3h3.5zM5 9v3h4.5a1.5 1.5 0 000-3H5z">
-                </path>
-            </svg>
-        }
-    }
-}
-
-impl Widget for Bold {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(textarea_selection(&selector), ("**", "**"), mode)
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
-    }
-}
-
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Italic(pub Tool);
-
-impl Italic {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Italic".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::Word,
-        })
-    }
-
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
+        self.size = size;
         self
     }
 
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
+    pub fn with_shortcut(mut self, shortcut: impl Into<Option<Shortcut>>) -> Self {
+        self.shortcut = shortcut.into();
         self
     }
 
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
+    pub fn id(&self) -> &str {
+        self.action.id()
     }
 
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M6 2.75A.75.75 0 016.75 2h6.5a.75.75 0 010 1.5h-2.505l-3.858 \
-                        9H9.25a.75.75 0 010 1.5h-6.5a.75.75 0 010-1.5h2.505l3.858-9H6.75A.75.75 0 016 2.75z">
-                </path>
-            </svg>
+    pub fn full_title(&self) -> String {
+        match &self.shortcut {
+            Some(shortcut) => format!("{} ({})", self.title, shortcut),
+            None => self.title.clone(),
         }
     }
 }
 
-impl Widget for Italic {
+impl Widget for Tool {
     fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(textarea_selection(&selector), ("*", "*"), mode)
-        });
+        let action = self.action.clone();
+        let onclick = Callback::from(move |textarea: HtmlTextAreaElement| action.run(&textarea));
         html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
+            <ToolButton class = { self.class.clone() } title = { self.full_title() } size = { self.size }
+                    textarea_selector = { self.textarea_selector.clone() } { onclick }>
+                { self.action.icon(self.size) }
+            </ToolButton>
         }
     }
-}
-
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Quote(pub Tool);
-
-impl Quote {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Quote".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::FromWordToEndLine,
-        })
-    }
-
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
-    }
 
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
-    }
-
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
-
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M1.75 2.5a.75.75 0 000 1.5h10.5a.75.75 0 000-1.5H1.75zm4 5a.75.75 \
-                        0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zM2.5 \
-                        7.75a.75.75 0 00-1.5 0v6a.75.75 0 001.5 0v-6z">
-                </path>
-            </svg>
+    fn key_binding(&self) -> Option<(Shortcut, Action)> {
+        if !self.action.binds_shortcut() {
+            return None;
         }
-    }
-}
 
-impl Widget for Quote {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(
-                textarea_selection(&selector),
-                ReplaceFmt::StartLine("> ".to_string()),
-                mode,
+        let action = self.action.clone();
+        self.shortcut.clone().map(|shortcut| {
+            (
+                shortcut,
+                Rc::new(move |state: &mut EditorState| action.apply(state)) as Action,
             )
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
-    }
-}
-
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Code(pub Tool);
-
-impl Code {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Code".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::Word,
         })
     }
+}
 
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
-    }
-
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
-    }
-
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
+#[derive(Properties, PartialEq)]
+pub struct ToolButtonProps {
+    pub class: String,
+    pub title: String,
+    pub size: u32,
+    pub textarea_selector: String,
+    pub onclick: Callback<HtmlTextAreaElement>,
 
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M4.72 3.22a.75.75 0 011.06 1.06L2.06 8l3.72 3.72a.75.75 0 11-1.06 \
-                        1.06L.47 8.53a.75.75 0 010-1.06l4.25-4.25zm6.56 0a.75.75 0 10-1.06 1.06L13.94 8l-3.72 \
-                        3.72a.75.75 0 101.06 1.06l4.25-4.25a.75.75 0 000-1.06l-4.25-4.25z">
-                </path>
-            </svg>
-        }
-    }
+    #[prop_or_default]
+    pub children: Html,
 }
 
-impl Widget for Code {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            let selection = textarea_selection(&selector);
-            let (prefix, suffix) = selection
+#[function_component(ToolButton)]
+pub fn tool_button(props: &ToolButtonProps) -> Html {
+    let context = use_context::<EditorContext>();
+    let onclick = {
+        let onclick = props.onclick.clone();
+        let selector = props.textarea_selector.clone();
+        Callback::from(move |_: MouseEvent| {
+            let textarea = context
                 .as_ref()
-                .and_then(|(_, text, selection)| {
-                    text.chars()
-                        .skip(selection.start)
-                        .take(selection.end)
-                        .find(|&ch| ch == '\n')
-                })
-                .map(|_| ("\n```\n", "\n```\n"))
-                .unwrap_or(("`", "`"));
-            replace_selected_in_textarea(
-                selection,
-                ReplaceFmt::Around(prefix.to_string(), suffix.to_string()),
-                mode,
-            )
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
-    }
-}
-
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct Link(pub Tool);
-
-impl Link {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Link".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::Word,
+                .and_then(EditorContext::textarea)
+                .or_else(|| find_textarea(&selector));
+            if let Some(textarea) = textarea {
+                onclick.emit(textarea);
+            }
         })
-    }
+    };
 
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
-    }
-
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
-    }
-
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
-
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M7.775 3.275a.75.75 0 001.06 1.06l1.25-1.25a2 2 0 112.83 2.83l-2.5 \
-                        2.5a2 2 0 01-2.83 0 .75.75 0 00-1.06 1.06 3.5 3.5 0 004.95 0l2.5-2.5a3.5 3.5 0 00-4.95-4.95l-1.25 \
-                        1.25zm-4.69 9.64a2 2 0 010-2.83l2.5-2.5a2 2 0 012.83 0 .75.75 0 001.06-1.06 3.5 3.5 0 00-4.95 \
-                        0l-2.5 2.5a3.5 3.5 0 004.95 4.95l1.25-1.25a.75.75 0 00-1.06-1.06l-1.25 1.25a2 2 0 01-2.83 0z">
-                </path>
-            </svg>
-        }
+    html! {
+        <button class = { props.class.clone() } style = { format!("width: {0}; height: {0}", props.size) }
+                type = "button" title = { props.title.clone() } { onclick }>
+            { props.children.clone() }
+        </button>
     }
 }
 
-impl Widget for Link {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(textarea_selection(&selector), ("[", "]()"), mode)
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
+pub fn svg_icon(path: &str, size: u32) -> Html {
+    html! {
+        <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
+            <path fill-rule = "evenodd" d = { path.to_string() }></path>
+        </svg>
     }
 }
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct ImageLink(pub Tool);
-
-impl ImageLink {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Image link".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::Word,
-        })
-    }
-
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
-    }
-
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
-    }
-
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
-
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M1.75 2.5a.25.25 0 00-.25.25v10.5c0 .138.112.25.25.25h.94a.76.76 0 \
-                        01.03-.03l6.077-6.078a1.75 1.75 0 012.412-.06L14.5 10.31V2.75a.25.25 0 00-.25-.25H1.75zm12.5 \
-                        11H4.81l5.048-5.047a.25.25 0 01.344-.009l4.298 3.889v.917a.25.25 0 01-.25.25zm1.75-.25V2.75A1.75 \
-                        1.75 0 0014.25 1H1.75A1.75 1.75 0 000 2.75v10.5C0 14.216.784 15 1.75 15h12.5A1.75 1.75 0 0016 \
-                        13.25zM5.5 6a.5.5 0 11-1 0 .5.5 0 011 0zM7 6a2 2 0 11-4 0 2 2 0 014 0z">
-                </path>
-            </svg>
-        }
-    }
+#[derive(Clone)]
+pub struct CustomAction {
+    pub id: String,
+    pub title: String,
+    pub icon: Option<Html>,
+    pub shortcut: Option<Shortcut>,
+    pub apply: Rc<dyn Fn(&mut EditorState)>,
 }
 
-impl Widget for ImageLink {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(textarea_selection(&selector), ("![", "]()"), mode)
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
+impl CustomAction {
+    pub fn new(id: impl Into<String>, title: impl Into<String>, apply: impl Fn(&mut EditorState) + 'static) -> Self {
+        Self {
+            id: id.into(),
+            title: title.into(),
+            icon: None,
+            shortcut: None,
+            apply: Rc::new(apply),
         }
     }
-}
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct BulletedList(pub Tool);
-
-impl BulletedList {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Bulleted list".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::FromWordToEndLine,
-        })
-    }
-
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
-    }
-
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
-    }
-
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
+    pub fn with_icon(mut self, icon: Html) -> Self {
+        self.icon = Some(icon);
         self
     }
 
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
+    pub fn with_shortcut(mut self, shortcut: impl Into<Option<Shortcut>>) -> Self {
+        self.shortcut = shortcut.into();
         self
     }
-
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M2 4a1 1 0 100-2 1 1 0 000 2zm3.75-1.5a.75.75 0 000 1.5h8.5a.75.75 \
-                        0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 \
-                        0 000-1.5h-8.5zM3 8a1 1 0 11-2 0 1 1 0 012 0zm-1 6a1 1 0 100-2 1 1 0 000 2z">
-                </path>
-            </svg>
-        }
-    }
 }
 
-impl Widget for BulletedList {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(
-                textarea_selection(&selector),
-                ReplaceFmt::StartLine("- ".to_string()),
-                mode,
-            )
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
-        }
+impl EditorAction for CustomAction {
+    fn id(&self) -> &str {
+        &self.id
     }
-}
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct OrderedList(pub Tool);
-
-impl OrderedList {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Ordered list".to_string(),
-            size: 16,
-            mode: UnselectedApplyMode::FromWordToEndLine,
-        })
+    fn title(&self) -> &str {
+        &self.title
     }
 
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
+    fn icon(&self, _size: u32) -> Html {
+        self.icon.clone().unwrap_or_else(|| html! { { &self.title } })
     }
 
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
+    fn apply(&self, state: &mut EditorState) {
+        (self.apply)(state)
     }
 
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
+    fn shortcut(&self) -> Option<Shortcut> {
+        self.shortcut.clone()
     }
+}
 
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltinAction {
+    Header(HeadingStyle),
+    Heading(usize, HeadingStyle),
+    Bold,
+    Italic,
+    Strikethrough,
+    Highlight,
+    Superscript,
+    Subscript,
+    Underline,
+    Quote,
+    Code,
+    CodeBlock,
+    Link,
+    ImageLink,
+    BulletedList,
+    OrderedList,
+    TaskList,
+    ToggleTask,
+    Table(usize, usize),
+    Undo,
+    Redo,
+}
 
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M2.003 2.5a.5.5 0 00-.723-.447l-1.003.5a.5.5 0 00.446.895l.28-.14V6H.5a.5.5 \
-                        0 000 1h2.006a.5.5 0 100-1h-.503V2.5zM5 3.25a.75.75 0 01.75-.75h8.5a.75.75 0 010 1.5h-8.5A.75.75 0 \
-                        015 3.25zm0 5a.75.75 0 01.75-.75h8.5a.75.75 0 010 1.5h-8.5A.75.75 0 015 8.25zm0 5a.75.75 0 \
-                        01.75-.75h8.5a.75.75 0 010 1.5h-8.5a.75.75 0 01-.75-.75zM.924 10.32l.003-.004a.851.851 0 \
-                        01.144-.153A.66.66 0 011.5 10c.195 0 .306.068.374.146a.57.57 0 01.128.376c0 .453-.269.682-.8 \
-                        1.078l-.035.025C.692 11.98 0 12.495 0 13.5a.5.5 0 00.5.5h2.003a.5.5 0 \
-                        000-1H1.146c.132-.197.351-.372.654-.597l.047-.035c.47-.35 1.156-.858 1.156-1.845 \
-                        0-.365-.118-.744-.377-1.038-.268-.303-.658-.484-1.126-.484-.48 0-.84.202-1.068.392a1.858 1.858 0 \
-                        00-.348.384l-.007.011-.002.004-.001.002-.001.001a.5.5 0 00.851.525zM.5 10.055l-.427-.26.427.26z">
-                </path>
-            </svg>
+impl BuiltinAction {
+    pub fn mode(&self) -> UnselectedApplyMode {
+        match self {
+            Self::Header(_) | Self::Heading(..) => UnselectedApplyMode::Line,
+            Self::Quote | Self::BulletedList | Self::OrderedList | Self::TaskList => {
+                UnselectedApplyMode::FromWordToEndLine
+            },
+            _ => UnselectedApplyMode::Word,
         }
     }
-}
 
-impl Widget for OrderedList {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(
-                textarea_selection(&selector),
-                ReplaceFmt::StartLine("1. ".to_string()),
-                mode,
-            )
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(self.size) }
-            </button>
+    fn icon_path(&self) -> &'static str {
+        match self {
+            Self::Header(_) | Self::Heading(..) => HEADER_ICON,
+            Self::Bold => BOLD_ICON,
+            Self::Italic => ITALIC_ICON,
+            Self::Strikethrough => STRIKETHROUGH_ICON,
+            Self::Highlight => HIGHLIGHT_ICON,
+            Self::Superscript => SUPERSCRIPT_ICON,
+            Self::Subscript => SUBSCRIPT_ICON,
+            Self::Underline => UNDERLINE_ICON,
+            Self::Quote => QUOTE_ICON,
+            Self::Code => CODE_ICON,
+            Self::CodeBlock => CODE_BLOCK_ICON,
+            Self::Link => LINK_ICON,
+            Self::ImageLink => IMAGE_LINK_ICON,
+            Self::BulletedList => BULLETED_LIST_ICON,
+            Self::OrderedList => ORDERED_LIST_ICON,
+            Self::TaskList => TASK_LIST_ICON,
+            Self::ToggleTask => TOGGLE_TASK_ICON,
+            Self::Table(..) => TABLE_ICON,
+            Self::Undo => UNDO_ICON,
+            Self::Redo => REDO_ICON,
         }
     }
 }
 
-#[derive(Debug, Clone, Default, Deref, DerefMut)]
-pub struct TaskList(pub Tool);
-
-impl TaskList {
-    pub fn new() -> Self {
-        Self(Tool {
-            textarea_selector: ".lew-simple__textarea".to_string(),
-            class: "lew-simple__tool_button".to_string(),
-            title: "Task list".to_string(),
-            size: 24,
-            mode: UnselectedApplyMode::FromWordToEndLine,
-        })
+impl EditorAction for BuiltinAction {
+    fn id(&self) -> &str {
+        match self {
+            Self::Header(_) => "header",
+            Self::Heading(level, _) => match level {
+                1 => "heading-1",
+                2 => "heading-2",
+                3 => "heading-3",
+                4 => "heading-4",
+                5 => "heading-5",
+                _ => "heading-6",
+            },
+            Self::Bold => "bold",
+            Self::Italic => "italic",
+            Self::Strikethrough => "strikethrough",
+            Self::Highlight => "highlight",
+            Self::Superscript => "superscript",
+            Self::Subscript => "subscript",
+            Self::Underline => "underline",
+            Self::Quote => "quote",
+            Self::Code => "code",
+            Self::CodeBlock => "code-block",
+            Self::Link => "link",
+            Self::ImageLink => "image-link",
+            Self::BulletedList => "bulleted-list",
+            Self::OrderedList => "ordered-list",
+            Self::TaskList => "task-list",
+            Self::ToggleTask => "toggle-task",
+            Self::Table(..) => "table",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+        }
     }
 
-    pub fn with_textarea_selector(mut self, selector: impl Into<String>) -> Self {
-        self.0.textarea_selector = selector.into();
-        self
+    fn title(&self) -> &str {
+        match self {
+            Self::Header(_) => "Header",
+            Self::Heading(level, _) => match level {
+                1 => "Heading 1",
+                2 => "Heading 2",
+                3 => "Heading 3",
+                4 => "Heading 4",
+                5 => "Heading 5",
+                _ => "Heading 6",
+            },
+            Self::Bold => "Bold",
+            Self::Italic => "Italic",
+            Self::Strikethrough => "Strikethrough",
+            Self::Highlight => "Highlight",
+            Self::Superscript => "Superscript",
+            Self::Subscript => "Subscript",
+            Self::Underline => "Underline",
+            Self::Quote => "Quote",
+            Self::Code => "Code",
+            Self::CodeBlock => "Code block",
+            Self::Link => "Link",
+            Self::ImageLink => "Image link",
+            Self::BulletedList => "Bulleted list",
+            Self::OrderedList => "Ordered list",
+            Self::TaskList => "Task list",
+            Self::ToggleTask => "Toggle task",
+            Self::Table(..) => "Table",
+            Self::Undo => "Undo",
+            Self::Redo => "Redo",
+        }
     }
 
-    pub fn with_class(mut self, class: impl Into<String>) -> Self {
-        self.0.class = class.into();
-        self
+    fn icon(&self, size: u32) -> Html {
+        svg_icon(self.icon_path(), size)
+    }
+
+    fn apply(&self, state: &mut EditorState) {
+        let mode = self.mode();
+        match self {
+            Self::Header(style) => {
+                heading::cycle_heading(state, *style);
+            },
+            Self::Heading(level, style) => {
+                heading::set_heading(state, Some(*level), *style);
+            },
+            Self::Bold => state.replace(("**", "**"), mode),
+            Self::Italic => state.replace(("*", "*"), mode),
+            Self::Strikethrough => state.replace(("~~", "~~"), mode),
+            Self::Highlight => state.replace(("==", "=="), mode),
+            Self::Superscript => state.replace(("<sup>", "</sup>"), mode),
+            Self::Subscript => state.replace(("<sub>", "</sub>"), mode),
+            Self::Underline => state.replace(("<ins>", "</ins>"), mode),
+            Self::Quote => state.replace(ReplaceFmt::StartLine("> ".to_string()), mode),
+            Self::Code => {
+                if state.selected_text().contains('\n') {
+                    code::toggle_code_block(state, "");
+                } else {
+                    state.replace(("`", "`"), mode);
+                }
+            },
+            Self::CodeBlock => {
+                code::toggle_code_block(state, "");
+            },
+            Self::Link => state.replace(("[", "]()"), mode),
+            Self::ImageLink => state.replace(("![", "]()"), mode),
+            Self::BulletedList => {
+                list::toggle_list(state, ListKind::Bulleted);
+            },
+            Self::OrderedList => {
+                list::toggle_list(state, ListKind::Ordered);
+            },
+            Self::TaskList => {
+                list::toggle_list(state, ListKind::Task);
+            },
+            Self::ToggleTask => {
+                list::toggle_tasks(state);
+            },
+            Self::Table(rows, columns) => table::insert_table(state, *rows, *columns),
+            Self::Undo | Self::Redo => (),
+        }
     }
 
-    pub fn with_title(mut self, title: impl Into<String>) -> Self {
-        self.0.title = title.into();
-        self
+    fn shortcut(&self) -> Option<Shortcut> {
+        match self {
+            Self::Bold => Some(Shortcut::ctrl("b")),
+            Self::Italic => Some(Shortcut::ctrl("i")),
+            Self::Strikethrough => Some(Shortcut::ctrl("x").with_shift()),
+            Self::Underline => Some(Shortcut::ctrl("u")),
+            Self::Quote => Some(Shortcut::ctrl(".").with_shift()),
+            Self::Code => Some(Shortcut::ctrl("e")),
+            Self::CodeBlock => Some(Shortcut::ctrl("e").with_shift()),
+            Self::Link => Some(Shortcut::ctrl("k")),
+            Self::BulletedList => Some(Shortcut::ctrl("8").with_shift()),
+            Self::OrderedList => Some(Shortcut::ctrl("7").with_shift()),
+            Self::ToggleTask => Some(Shortcut::ctrl("Enter")),
+            Self::Undo => Some(Shortcut::ctrl("z")),
+            Self::Redo => Some(Shortcut::ctrl("z").with_shift()),
+            Self::Heading(level, _) if *level <= heading::MAX_HEADING_LEVEL => {
+                Some(Shortcut::ctrl(level.to_string()).with_alt())
+            },
+            Self::Header(_)
+            | Self::Heading(..)
+            | Self::Highlight
+            | Self::Superscript
+            | Self::Subscript
+            | Self::ImageLink
+            | Self::TaskList
+            | Self::Table(..) => None,
+        }
     }
 
-    pub fn with_size(mut self, size: u32) -> Self {
-        self.0.size = size;
-        self
+    fn binds_shortcut(&self) -> bool {
+        !matches!(self, Self::Undo | Self::Redo)
     }
 
-    pub fn svg_icon(size: u32) -> Html {
-        html! {
-            <svg xmlns = "http://www.w3.org/2000/svg" viewBox = "0 0 16 16" width = { size.to_string() } height = { size.to_string() }>
-                <path fill-rule = "evenodd" d = "M2.5 2.75a.25.25 0 01.25-.25h10.5a.25.25 0 01.25.25v10.5a.25.25 0 \
-                        01-.25.25H2.75a.25.25 0 01-.25-.25V2.75zM2.75 1A1.75 1.75 0 001 2.75v10.5c0 .966.784 1.75 \
-                        1.75 1.75h10.5A1.75 1.75 0 0015 13.25V2.75A1.75 1.75 0 0013.25 1H2.75zm9.03 5.28a.75.75 0 \
-                        00-1.06-1.06L6.75 9.19 5.28 7.72a.75.75 0 00-1.06 1.06l2 2a.75.75 0 001.06 0l4.5-4.5z">
-                </path>
-            </svg>
+    fn run(&self, textarea: &HtmlTextAreaElement) {
+        match self {
+            Self::Undo => dispatch_shortcut(textarea, "z", false),
+            Self::Redo => dispatch_shortcut(textarea, "z", true),
+            _ => edit_textarea(textarea, |state| self.apply(state)),
         }
     }
 }
 
-impl Widget for TaskList {
-    fn build(&self) -> Html {
-        let selector = self.textarea_selector.clone();
-        let mode = self.mode;
-        let onclick = Callback::from(move |_: MouseEvent| {
-            replace_selected_in_textarea(
-                textarea_selection(&selector),
-                ReplaceFmt::StartLine("- [ ] ".to_string()),
-                mode,
-            )
-        });
-        html! {
-            <button class = { &self.class } style = { format!("width: {0}; height: {0}", self.size) }
-                    type = "button" title = { self.title.clone() } { onclick }>
-                { Self::svg_icon(16) }
-            </button>
-        }
-    }
+fn dispatch_shortcut(textarea: &HtmlTextAreaElement, key: &str, shift: bool) {
+    let init = KeyboardEventInit::new();
+    init.set_key(key);
+    init.set_ctrl_key(true);
+    init.set_shift_key(shift);
+    init.set_bubbles(true);
+    let event = KeyboardEvent::new_with_keyboard_event_init_dict("keydown", &init).expect("Keydown event expected");
+    let target: &EventTarget = textarea.as_ref();
+    target.dispatch_event(&event).expect("Dispatch failed");
 }
+
+const HEADER_ICON: &str = "M3.75 2a.75.75 0 01.75.75V7h7V2.75a.75.75 0 011.5 0v10.5a.75.75 0 \
+    01-1.5 0V8.5h-7v4.75a.75.75 0 01-1.5 0V2.75A.75.75 0 013.75 2z";
+
+const BOLD_ICON: &str = "M4 2a1 1 0 00-1 1v10a1 1 0 001 1h5.5a3.5 3.5 0 001.852-6.47A3.5 3.5 0 \
+    008.5 2H4zm4.5 5a1.5 1.5 0 100-3H5v3h3.5zM5 9v3h4.5a1.5 1.5 0 000-3H5z";
+
+const ITALIC_ICON: &str = "M6 2.75A.75.75 0 016.75 2h6.5a.75.75 0 010 1.5h-2.505l-3.858 \
+    9H9.25a.75.75 0 010 1.5h-6.5a.75.75 0 010-1.5h2.505l3.858-9H6.75A.75.75 0 016 2.75z";
+
+const STRIKETHROUGH_ICON: &str = "M7.581 3.25c-2.036 0-2.778 1.082-2.778 1.786 0 .055.002.107.006.157a.75.75 \
+    0 01-1.496.114 3.56 3.56 0 01-.01-.271c0-1.832 1.75-3.286 4.278-3.286 1.418 0 2.721.58 3.514 1.093a.75.75 0 \
+    11-.814 1.26c-.64-.414-1.662-.853-2.7-.853zm3.474 5.25h3.195a.75.75 0 000-1.5H1.75a.75.75 0 000 1.5h6.018c.835.187 \
+    1.503.464 1.951.81.439.34.647.725.647 1.197 0 .428-.193.874-.673 1.227-.485.356-1.298.647-2.51.647-1.265 \
+    0-2.449-.39-3.146-.8a.75.75 0 00-.76 1.293c.913.537 2.333.997 3.906.997 1.44 0 2.595-.342 3.398-.932.81-.596 \
+    1.235-1.43 1.235-2.335 0-.443-.082-.837-.216-1.182z";
+
+const HIGHLIGHT_ICON: &str = "M11.134 1.28a1.25 1.25 0 011.768 0l1.818 1.818a1.25 1.25 0 010 1.768L8.56 \
+    11.027a1.25 1.25 0 01-.67.348l-2.65.442a.75.75 0 01-.863-.863l.442-2.65a1.25 1.25 0 01.348-.67l6.161-6.16zm.884 \
+    1.237L6.045 8.49l-.284 1.703 1.703-.284 5.973-5.973-1.419-1.419zM1.75 13.5h12.5a.75.75 0 010 1.5H1.75a.75.75 0 \
+    010-1.5z";
+
+const SUPERSCRIPT_ICON: &str = "M2.22 5.22a.75.75 0 011.06 0L5.5 7.44l2.22-2.22a.75.75 0 111.06 1.06L6.56 \
+    8.5l2.22 2.22a.75.75 0 11-1.06 1.06L5.5 9.56l-2.22 2.22a.75.75 0 01-1.06-1.06L4.44 8.5 2.22 6.28a.75.75 0 \
+    010-1.06zM11 1.5h3.5V5H11V1.5z";
+
+const SUBSCRIPT_ICON: &str = "M2.22 3.22a.75.75 0 011.06 0L5.5 5.44l2.22-2.22a.75.75 0 111.06 1.06L6.56 \
+    6.5l2.22 2.22a.75.75 0 11-1.06 1.06L5.5 7.56 3.28 9.78a.75.75 0 01-1.06-1.06L4.44 6.5 2.22 4.28a.75.75 0 \
+    010-1.06zM11 11h3.5v3.5H11V11z";
+
+const UNDERLINE_ICON: &str = "M4.75 1.5a.75.75 0 01.75.75V7a2.5 2.5 0 005 0V2.25a.75.75 0 011.5 0V7a4 4 0 \
+    01-8 0V2.25a.75.75 0 01.75-.75zM2.75 13.5h10.5a.75.75 0 010 1.5H2.75a.75.75 0 010-1.5z";
+
+const QUOTE_ICON: &str = "M1.75 2.5a.75.75 0 000 1.5h10.5a.75.75 0 000-1.5H1.75zm4 5a.75.75 \
+    0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zM2.5 \
+    7.75a.75.75 0 00-1.5 0v6a.75.75 0 001.5 0v-6z";
+
+const CODE_ICON: &str = "M4.72 3.22a.75.75 0 011.06 1.06L2.06 8l3.72 3.72a.75.75 0 11-1.06 \
+    1.06L.47 8.53a.75.75 0 010-1.06l4.25-4.25zm6.56 0a.75.75 0 10-1.06 1.06L13.94 8l-3.72 \
+    3.72a.75.75 0 101.06 1.06l4.25-4.25a.75.75 0 000-1.06l-4.25-4.25z";
+
+const CODE_BLOCK_ICON: &str = "M0 1.75C0 .784.784 0 1.75 0h12.5C15.216 0 16 .784 16 1.75v12.5A1.75 1.75 0 \
+    0114.25 16H1.75A1.75 1.75 0 010 14.25V1.75zm1.75-.25a.25.25 0 00-.25.25v12.5c0 .138.112.25.25.25h12.5a.25.25 0 \
+    00.25-.25V1.75a.25.25 0 00-.25-.25H1.75zm7.47 3.97a.75.75 0 011.06 0l2 2a.75.75 0 010 1.06l-2 2a.75.75 0 \
+    11-1.06-1.06L10.69 8 9.22 6.53a.75.75 0 010-1.06zM6.78 6.53a.75.75 0 00-1.06-1.06l-2 2a.75.75 0 000 1.06l2 \
+    2a.75.75 0 101.06-1.06L5.31 8l1.47-1.47z";
+
+const LINK_ICON: &str = "M7.775 3.275a.75.75 0 001.06 1.06l1.25-1.25a2 2 0 112.83 2.83l-2.5 \
+    2.5a2 2 0 01-2.83 0 .75.75 0 00-1.06 1.06 3.5 3.5 0 004.95 0l2.5-2.5a3.5 3.5 0 00-4.95-4.95l-1.25 \
+    1.25zm-4.69 9.64a2 2 0 010-2.83l2.5-2.5a2 2 0 012.83 0 .75.75 0 001.06-1.06 3.5 3.5 0 00-4.95 \
+    0l-2.5 2.5a3.5 3.5 0 004.95 4.95l1.25-1.25a.75.75 0 00-1.06-1.06l-1.25 1.25a2 2 0 01-2.83 0z";
+
+const IMAGE_LINK_ICON: &str = "M1.75 2.5a.25.25 0 00-.25.25v10.5c0 .138.112.25.25.25h.94a.76.76 0 \
+    01.03-.03l6.077-6.078a1.75 1.75 0 012.412-.06L14.5 10.31V2.75a.25.25 0 00-.25-.25H1.75zm12.5 \
+    11H4.81l5.048-5.047a.25.25 0 01.344-.009l4.298 3.889v.917a.25.25 0 01-.25.25zm1.75-.25V2.75A1.75 \
+    1.75 0 0014.25 1H1.75A1.75 1.75 0 000 2.75v10.5C0 14.216.784 15 1.75 15h12.5A1.75 1.75 0 0016 \
+    13.25zM5.5 6a.5.5 0 11-1 0 .5.5 0 011 0zM7 6a2 2 0 11-4 0 2 2 0 014 0z";
+
+const BULLETED_LIST_ICON: &str = "M2 4a1 1 0 100-2 1 1 0 000 2zm3.75-1.5a.75.75 0 000 1.5h8.5a.75.75 \
+    0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 0 000-1.5h-8.5zm0 5a.75.75 0 000 1.5h8.5a.75.75 \
+    0 000-1.5h-8.5zM3 8a1 1 0 11-2 0 1 1 0 012 0zm-1 6a1 1 0 100-2 1 1 0 000 2z";
+
+const ORDERED_LIST_ICON: &str = "M2.003 2.5a.5.5 0 00-.723-.447l-1.003.5a.5.5 0 00.446.895l.28-.14V6H.5a.5.5 \
+    0 000 1h2.006a.5.5 0 100-1h-.503V2.5zM5 3.25a.75.75 0 01.75-.75h8.5a.75.75 0 010 1.5h-8.5A.75.75 0 \
+    015 3.25zm0 5a.75.75 0 01.75-.75h8.5a.75.75 0 010 1.5h-8.5A.75.75 0 015 8.25zm0 5a.75.75 0 \
+    01.75-.75h8.5a.75.75 0 010 1.5h-8.5a.75.75 0 01-.75-.75zM.924 10.32l.003-.004a.851.851 0 \
+    01.144-.153A.66.66 0 011.5 10c.195 0 .306.068.374.146a.57.57 0 01.128.376c0 .453-.269.682-.8 \
+    1.078l-.035.025C.692 11.98 0 12.495 0 13.5a.5.5 0 00.5.5h2.003a.5.5 0 \
+    000-1H1.146c.132-.197.351-.372.654-.597l.047-.035c.47-.35 1.156-.858 1.156-1.845 \
+    0-.365-.118-.744-.377-1.038-.268-.303-.658-.484-1.126-.484-.48 0-.84.202-1.068.392a1.858 1.858 0 \
+    00-.348.384l-.007.011-.002.004-.001.002-.001.001a.5.5 0 00.851.525zM.5 10.055l-.427-.26.427.26z";
+
+const TASK_LIST_ICON: &str = "M2.5 2.75a.25.25 0 01.25-.25h10.5a.25.25 0 01.25.25v10.5a.25.25 0 \
+    01-.25.25H2.75a.25.25 0 01-.25-.25V2.75zM2.75 1A1.75 1.75 0 001 2.75v10.5c0 .966.784 1.75 \
+    1.75 1.75h10.5A1.75 1.75 0 0015 13.25V2.75A1.75 1.75 0 0013.25 1H2.75zm9.03 5.28a.75.75 0 \
+    00-1.06-1.06L6.75 9.19 5.28 7.72a.75.75 0 00-1.06 1.06l2 2a.75.75 0 001.06 0l4.5-4.5z";
+
+const TOGGLE_TASK_ICON: &str = "M13.78 4.22a.75.75 0 010 1.06l-7.25 7.25a.75.75 0 01-1.06 0L2.22 \
+    9.28a.75.75 0 011.06-1.06L6 10.94l6.72-6.72a.75.75 0 011.06 0z";
+
+const UNDO_ICON: &str = "M1.22 6.28a.75.75 0 010-1.06l3.5-3.5a.75.75 0 111.06 1.06L3.56 5h6.94a4.5 \
+    4.5 0 010 9H8.8a.75.75 0 010-1.5h1.7a3 3 0 000-6H3.56l2.22 2.22a.75.75 0 11-1.06 1.06l-3.5-3.5z";
+
+const REDO_ICON: &str = "M14.78 6.28a.75.75 0 000-1.06l-3.5-3.5a.75.75 0 10-1.06 1.06L12.44 5H5.5a4.5 \
+    4.5 0 000 9h1.7a.75.75 0 000-1.5H5.5a3 3 0 010-6h6.94l-2.22 2.22a.75.75 0 101.06 1.06l3.5-3.5z";
+
+const TABLE_ICON: &str = "M1.75 2h12.5c.966 0 1.75.784 1.75 1.75v8.5A1.75 1.75 0 0114.25 14H1.75A1.75 1.75 0 \
+    010 12.25v-8.5C0 2.784.784 2 1.75 2zM1.5 7v5.25c0 .138.112.25.25.25H5V7H1.5zm5 0v5.5h3V7h-3zm4.5 0v5.5h3.25a.25.25 \
+    0 00.25-.25V7H11zm3.5-1.5V3.75a.25.25 0 00-.25-.25H1.75a.25.25 0 00-.25.25V5.5h13z";
@@ -0,0 +1,108 @@
+use std::iter;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SelectionUnit {
+    Utf16,
+    #[default]
+    Char,
+    Byte,
+}
+
+impl SelectionUnit {
+    pub fn char_len(&self, ch: char) -> usize {
+        match self {
+            SelectionUnit::Utf16 => ch.len_utf16(),
+            SelectionUnit::Char => 1,
+            SelectionUnit::Byte => ch.len_utf8(),
+        }
+    }
+
+    pub fn text_len(&self, text: &str) -> usize {
+        match self {
+            SelectionUnit::Utf16 => text.encode_utf16().count(),
+            SelectionUnit::Char => text.chars().count(),
+            SelectionUnit::Byte => text.len(),
+        }
+    }
+
+    pub fn convert(&self, text: &str, offset: usize, unit: SelectionUnit) -> usize {
+        let mut source = 0;
+        let mut target = 0;
+        for ch in text.chars() {
+            source += self.char_len(ch);
+            if source > offset {
+                break;
+            }
+            target += unit.char_len(ch);
+        }
+        target
+    }
+}
+
+pub fn grapheme_boundaries(text: &str) -> impl Iterator<Item = usize> + '_ {
+    iter::once(0).chain(text.graphemes(true).scan(0, |pos, grapheme| {
+        *pos += grapheme.chars().count();
+        Some(*pos)
+    }))
+}
+
+pub fn grapheme_floor(text: &str, pos: usize) -> usize {
+    grapheme_boundaries(text)
+        .filter(|&boundary| boundary <= pos)
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn grapheme_ceil(text: &str, pos: usize) -> usize {
+    grapheme_boundaries(text)
+        .filter(|&boundary| boundary >= pos)
+        .min()
+        .unwrap_or_else(|| text.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn convert_counts_surrogate_pairs_and_bytes() {
+        let text = "a😀b";
+        assert_eq!(SelectionUnit::Utf16.convert(text, 3, SelectionUnit::Char), 2);
+        assert_eq!(SelectionUnit::Char.convert(text, 2, SelectionUnit::Utf16), 3);
+        assert_eq!(SelectionUnit::Char.convert(text, 2, SelectionUnit::Byte), 5);
+        assert_eq!(SelectionUnit::Byte.convert(text, 6, SelectionUnit::Utf16), 4);
+    }
+
+    #[test]
+    fn convert_rounds_down_inside_a_char() {
+        let text = "a😀b";
+        assert_eq!(SelectionUnit::Utf16.convert(text, 2, SelectionUnit::Char), 1);
+        assert_eq!(SelectionUnit::Byte.convert(text, 3, SelectionUnit::Char), 1);
+    }
+
+    #[test]
+    fn convert_clamps_past_the_end() {
+        assert_eq!(SelectionUnit::Utf16.convert("ab", 10, SelectionUnit::Char), 2);
+    }
+
+    #[test]
+    fn grapheme_bounds_keep_clusters_whole() {
+        let family = "a👩\u{200d}👩\u{200d}👧b";
+        assert_eq!(grapheme_boundaries(family).collect::<Vec<_>>(), vec![0, 1, 6, 7]);
+        assert_eq!(grapheme_floor(family, 3), 1);
+        assert_eq!(grapheme_ceil(family, 3), 6);
+
+        let skin_tone = "👍🏽";
+        assert_eq!(grapheme_floor(skin_tone, 1), 0);
+        assert_eq!(grapheme_ceil(skin_tone, 1), 2);
+
+        let combining = "e\u{301}x";
+        assert_eq!(grapheme_ceil(combining, 1), 2);
+
+        let cjk = "漢字";
+        assert_eq!(grapheme_floor(cjk, 1), 1);
+        assert_eq!(grapheme_ceil(cjk, 1), 1);
+    }
+}
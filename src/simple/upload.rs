@@ -0,0 +1,269 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::future::{self, Future};
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use js_sys::Uint8Array;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{File, HtmlTextAreaElement};
+use yew::platform::spawn_local;
+use yew::Callback;
+
+use crate::toolbar::link::LinkDraft;
+use crate::toolbar::{EditorState, Selection};
+
+pub type UploadFuture = Pin<Box<dyn Future<Output = Result<String, UploadError>>>>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadFile {
+    pub name: String,
+    pub mime_type: String,
+    pub data: Vec<u8>,
+}
+
+impl UploadFile {
+    pub fn new(name: impl Into<String>, mime_type: impl Into<String>, data: impl Into<Vec<u8>>) -> Self {
+        Self {
+            name: name.into(),
+            mime_type: mime_type.into(),
+            data: data.into(),
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        is_image_type(&self.mime_type)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UploadError {
+    pub file_name: String,
+    pub message: String,
+}
+
+impl UploadError {
+    pub fn new(file_name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for UploadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed to upload {}: {}", self.file_name, self.message)
+    }
+}
+
+pub trait Uploader {
+    fn upload(&self, file: UploadFile) -> UploadFuture;
+}
+
+#[derive(Clone)]
+pub struct UploaderRef(pub Rc<dyn Uploader>);
+
+impl UploaderRef {
+    pub fn new(uploader: impl Uploader + 'static) -> Self {
+        Self(Rc::new(uploader))
+    }
+}
+
+impl PartialEq for UploaderRef {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for UploaderRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("UploaderRef")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryUploader {
+    pub base_url: String,
+    files: Rc<RefCell<Vec<UploadFile>>>,
+}
+
+impl InMemoryUploader {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            files: Default::default(),
+        }
+    }
+
+    pub fn files(&self) -> Vec<UploadFile> {
+        self.files.borrow().clone()
+    }
+}
+
+impl Uploader for InMemoryUploader {
+    fn upload(&self, file: UploadFile) -> UploadFuture {
+        let mut files = self.files.borrow_mut();
+        let url = format!("{}{}/{}", self.base_url, files.len(), file.name.replace(' ', "%20"));
+        files.push(file);
+        Box::pin(future::ready(Ok(url)))
+    }
+}
+
+pub fn is_image_type(mime_type: &str) -> bool {
+    mime_type.starts_with("image/")
+}
+
+pub fn next_upload_id() -> usize {
+    static NEXT_UPLOAD_ID: AtomicUsize = AtomicUsize::new(0);
+    NEXT_UPLOAD_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+pub fn upload_placeholder(file_name: &str, is_image: bool, upload_id: usize) -> String {
+    let text = format!("Uploading {}…", escape_brackets(file_name));
+    if is_image {
+        format!("![{text}](#upload-{upload_id})")
+    } else {
+        format!("[{text}](#upload-{upload_id})")
+    }
+}
+
+pub fn uploaded_markdown(file_name: &str, is_image: bool, url: &str) -> String {
+    let text = escape_brackets(file_name);
+    let url = LinkDraft {
+        url: url.to_string(),
+        ..LinkDraft::default()
+    }
+    .destination();
+    if is_image {
+        format!("![{text}]({url})")
+    } else {
+        format!("[{text}]({url})")
+    }
+}
+
+pub fn insert_placeholder(state: &mut EditorState, placeholder: &str) {
+    state.insert_text(placeholder);
+}
+
+pub fn replace_placeholder(state: &mut EditorState, placeholder: &str, replacement: &str) -> bool {
+    let Some(byte_pos) = state.text.find(placeholder) else {
+        return false;
+    };
+
+    let selection = state.char_selection();
+    let start = state.text[..byte_pos].chars().count();
+    let old_end = start + placeholder.chars().count();
+    let new_end = start + replacement.chars().count();
+    let shift = |pos: usize| {
+        if pos <= start {
+            pos
+        } else if pos < old_end {
+            new_end
+        } else {
+            pos - old_end + new_end
+        }
+    };
+
+    state
+        .text
+        .replace_range(byte_pos..byte_pos + placeholder.len(), replacement);
+    state.selection = Selection::new(shift(selection.start), shift(selection.end));
+    true
+}
+
+pub fn upload_files(
+    textarea: HtmlTextAreaElement,
+    files: Vec<File>,
+    uploader: UploaderRef,
+    onerror: Callback<UploadError>,
+) {
+    for file in files {
+        let name = file.name();
+        let is_image = is_image_type(&file.type_());
+        let placeholder = upload_placeholder(&name, is_image, next_upload_id());
+
+        let mut state = EditorState::from_textarea(&textarea);
+        insert_placeholder(&mut state, &placeholder);
+        state.apply_to_textarea(&textarea);
+
+        let textarea = textarea.clone();
+        let uploader = uploader.clone();
+        let onerror = onerror.clone();
+        spawn_local(async move {
+            let result = match read_file(&file).await {
+                Ok(upload_file) => uploader.0.upload(upload_file).await,
+                Err(error) => Err(error),
+            };
+            let replacement = match result {
+                Ok(url) => uploaded_markdown(&name, is_image, &url),
+                Err(error) => {
+                    onerror.emit(error);
+                    String::new()
+                },
+            };
+
+            let mut state = EditorState::from_textarea(&textarea);
+            if replace_placeholder(&mut state, &placeholder, &replacement) {
+                state.update_textarea(&textarea);
+            }
+        });
+    }
+}
+
+pub async fn read_file(file: &File) -> Result<UploadFile, UploadError> {
+    let buffer = JsFuture::from(file.array_buffer())
+        .await
+        .map_err(|error| UploadError::new(file.name(), format!("{error:?}")))?;
+    Ok(UploadFile::new(
+        file.name(),
+        file.type_(),
+        Uint8Array::new(&buffer).to_vec(),
+    ))
+}
+
+fn escape_brackets(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_of_same_file_differ() {
+        let first = upload_placeholder("a.png", true, 1);
+        let second = upload_placeholder("a.png", true, 2);
+        assert_eq!(first, "![Uploading a.png…](#upload-1)");
+        assert_ne!(first, second);
+        assert_ne!(next_upload_id(), next_upload_id());
+    }
+
+    #[test]
+    fn replace_placeholder_targets_its_own_upload() {
+        let first = upload_placeholder("a.png", true, 1);
+        let second = upload_placeholder("a.png", true, 2);
+        let text = format!("{first}\n{second}");
+        let mut state = EditorState::new(text.clone(), 0..0);
+        assert!(replace_placeholder(&mut state, &second, "![a.png](b)"));
+        assert_eq!(state.text, format!("{first}\n![a.png](b)"));
+    }
+
+    #[test]
+    fn replace_placeholder_shifts_selection_after_it() {
+        let placeholder = upload_placeholder("a", false, 1);
+        let len = placeholder.chars().count();
+        let mut state = EditorState::new(format!("{placeholder} x"), len + 1..len + 2);
+        assert!(replace_placeholder(&mut state, &placeholder, "[a](u)"));
+        assert_eq!(state.selection, Selection::new(7, 8));
+        assert!(!replace_placeholder(&mut state, &placeholder, ""));
+    }
+
+    #[test]
+    fn uploaded_markdown_escapes_the_url() {
+        assert_eq!(uploaded_markdown("a].png", true, "/f/a.png"), "![a\\].png](/f/a.png)");
+        assert_eq!(uploaded_markdown("a", false, "/f/a b (1)"), "[a](</f/a b (1)>)");
+        assert_eq!(uploaded_markdown("a", false, "/f/<a>"), "[a](/f/%3Ca%3E)");
+    }
+}